@@ -0,0 +1,64 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use readervzrd::FileReader;
+use std::fs;
+use std::path::PathBuf;
+
+const ROWS: usize = 100_000;
+
+/// Writes a generated fixture with the given number of rows to the temporary directory and
+/// returns its path.
+fn fixture(name: &str, rows: usize) -> String {
+    let path: PathBuf = std::env::temp_dir().join(format!("readervzrd-bench-{}", name));
+    let content: String = match name {
+        "large.csv" => std::iter::once("id,name,score,flag\n".to_string())
+            .chain((0..rows).map(|i| format!("{},sample_{},{}.5,{}\n", i, i, i % 97, i % 2 == 0)))
+            .collect(),
+        "large.json" => format!(
+            "[{}]",
+            (0..rows)
+                .map(|i| format!(
+                    r#"{{"id":{},"name":"sample_{}","score":{}.5,"meta":{{"flag":{}}}}}"#,
+                    i,
+                    i,
+                    i % 97,
+                    i % 2 == 0
+                ))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        "large.ndjson" => (0..rows)
+            .map(|i| {
+                format!(
+                    "{{\"id\":{},\"name\":\"sample_{}\",\"score\":{}.5,\"meta\":{{\"flag\":{}}}}}\n",
+                    i,
+                    i,
+                    i % 97,
+                    i % 2 == 0
+                )
+            })
+            .collect(),
+        _ => unreachable!("unknown fixture {}", name),
+    };
+    fs::write(&path, content).expect("Failed to write fixture");
+    path.to_string_lossy().into_owned()
+}
+
+fn read_records(c: &mut Criterion) {
+    let mut group = c.benchmark_group("records");
+    group.throughput(Throughput::Elements(ROWS as u64));
+    group.sample_size(10);
+    for name in ["large.csv", "large.json", "large.ndjson"] {
+        let path = fixture(name, ROWS);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut reader = FileReader::new(&path, Some(',')).unwrap();
+                reader.headers().unwrap();
+                reader.records().unwrap().count()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, read_records);
+criterion_main!(benches);
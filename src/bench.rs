@@ -0,0 +1,99 @@
+use crate::FileReader;
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// Time spent in the stages of reading a file, see [`FileReader::timings`].
+/// Requires the `bench` feature.
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    pub(crate) detection: Cell<Duration>,
+    pub(crate) headers: Cell<Duration>,
+    pub(crate) decode: Cell<Duration>,
+    pub(crate) stringify: Cell<Duration>,
+}
+
+impl Timings {
+    /// Returns the time spent detecting the file format and compression when the reader was
+    /// created.
+    pub fn detection(&self) -> Duration {
+        self.detection.get()
+    }
+
+    /// Returns the time spent reading the headers in the last call of
+    /// [`FileReader::headers`].
+    pub fn headers(&self) -> Duration {
+        self.headers.get()
+    }
+
+    /// Returns the time spent parsing the records of the file in the last iteration.
+    pub fn decode(&self) -> Duration {
+        self.decode.get()
+    }
+
+    /// Returns the time spent selecting columns and normalizing values of the parsed records
+    /// in the last iteration.
+    pub fn stringify(&self) -> Duration {
+        self.stringify.get()
+    }
+
+    /// Resets the counters of an iteration over the records.
+    pub(crate) fn reset_records(&self) {
+        self.decode.set(Duration::ZERO);
+        self.stringify.set(Duration::ZERO);
+    }
+}
+
+impl FileReader {
+    /// Returns the time spent in the stages of reading the file, which allows to profile
+    /// readers with different options and file formats. Requires the `bench` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+    /// println!("Decoding took {:?}", reader.timings().decode());
+    /// ```
+    pub fn timings(&self) -> &Timings {
+        &self.timings
+    }
+}
+
+/// Adds the time elapsed since the given instant to the counter.
+pub(crate) fn add_elapsed(counter: &Cell<Duration>, start: Instant) {
+    counter.set(counter.get() + start.elapsed());
+}
+
+/// Wraps the iterator to add the time spent in each call of `next` to the counter.
+pub(crate) fn timed<'a, I: Iterator + 'a>(
+    mut iter: I,
+    counter: &'a Cell<Duration>,
+) -> impl Iterator<Item = I::Item> + 'a {
+    std::iter::from_fn(move || {
+        let start = Instant::now();
+        let item = iter.next();
+        add_elapsed(counter, start);
+        item
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timings() {
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        reader.headers().unwrap();
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records.len(), 3);
+        let timings = reader.timings();
+        assert!(timings.detection() > Duration::ZERO);
+        assert!(timings.headers() > Duration::ZERO);
+        assert!(timings.decode() > Duration::ZERO);
+        assert!(timings.stringify() > Duration::ZERO);
+    }
+}
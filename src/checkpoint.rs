@@ -0,0 +1,263 @@
+use crate::{
+    csv_reader_builder, finish_record, ErrorPolicy, FileError, FileFormat, FileReader, Warning,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// The position up to which the records of a file have been read by
+/// [`FileReader::resume_from`]. Checkpoints can be serialized to persist them between runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    byte_offset: u64,
+    records: u64,
+}
+
+impl Checkpoint {
+    /// Returns the byte offset following the last record that has been read.
+    pub fn byte_offset(&self) -> u64 {
+        self.byte_offset
+    }
+
+    /// Returns the number of records that have been read in total.
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+}
+
+impl FileReader {
+    /// Returns all records that have been appended to the file since the given checkpoint
+    /// together with a new checkpoint marking the end of the returned records.
+    /// Passing `Checkpoint::default()` returns all records of the file.
+    /// CSV files are resumed at the byte offset of the checkpoint, JSON files are re-read
+    /// and the already seen records are skipped.
+    /// A last CSV line without a line break may still be written to, so it is left for the
+    /// next call. Malformed CSV records are handled according to the [`ErrorPolicy`], with
+    /// [`ErrorPolicy::Strict`] the error is returned and the checkpoint is not advanced.
+    /// Line numbers of rejected rows and warnings are counted from the checkpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{Checkpoint, FileReader};
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let (records, checkpoint) = reader.resume_from(&Checkpoint::default()).unwrap();
+    /// // The last line has no line break yet.
+    /// assert_eq!(records.len(), 2);
+    /// let (records, _) = reader.resume_from(&checkpoint).unwrap();
+    /// assert!(records.is_empty());
+    /// ```
+    pub fn resume_from(
+        &mut self,
+        checkpoint: &Checkpoint,
+    ) -> Result<(Vec<Vec<String>>, Checkpoint), FileError> {
+        if let FileFormat::Csv(delimiter) = self.file_format {
+            let result = self.resume_csv_from(checkpoint, delimiter);
+            self.file.seek(SeekFrom::Start(0))?;
+            return result;
+        }
+        let records: Vec<Vec<String>> = self.records()?.skip(checkpoint.records as usize).collect();
+        let checkpoint = Checkpoint {
+            byte_offset: self.file.stream_position()?,
+            records: checkpoint.records + records.len() as u64,
+        };
+        self.file.seek(SeekFrom::Start(0))?;
+        Ok((records, checkpoint))
+    }
+
+    fn resume_csv_from(
+        &mut self,
+        checkpoint: &Checkpoint,
+        delimiter: char,
+    ) -> Result<(Vec<Vec<String>>, Checkpoint), FileError> {
+        let width = self.flexible_width()?;
+        self.file.seek(SeekFrom::Start(checkpoint.byte_offset))?;
+        self.rejected_rows.clear();
+        self.warnings.clear();
+        let mut reader = csv_reader_builder(delimiter, &self.csv_dialect)
+            .has_headers(checkpoint.byte_offset == 0)
+            .from_reader(LastByte {
+                inner: &mut self.file,
+                last: None,
+            });
+        let mut records = Vec::new();
+        let mut record = csv::StringRecord::new();
+        let mut end = reader.position().byte();
+        // A record is only taken once it is known to be complete, i.e. once another record
+        // follows or the file ends with a line break.
+        let mut pending: Option<(u64, Result<Vec<String>, csv::Error>, u64)> = None;
+        loop {
+            let next = match reader.read_record(&mut record) {
+                Ok(true) => Some((
+                    record.position().map_or(0, |position| position.line()),
+                    Ok(record.iter().map(|field| field.to_string()).collect()),
+                )),
+                Ok(false) => None,
+                Err(err) => Some((
+                    err.position().map_or(0, |position| position.line()),
+                    Err(err),
+                )),
+            }
+            .map(|(line, result)| (line, result, reader.position().byte()));
+            let complete = next.is_some() || matches!(reader.get_ref().last, Some(b'\n' | b'\r'));
+            if let (Some((line, result, record_end)), true) = (pending.take(), complete) {
+                match (result, self.error_policy) {
+                    (Ok(record), _) => {
+                        let (record, extra) = finish_record(
+                            record,
+                            width,
+                            self.columns.as_deref(),
+                            &self.normalization,
+                        );
+                        if extra > 0 {
+                            self.warnings
+                                .push(Warning::ExtraColumns { line, count: extra });
+                        }
+                        records.push(record);
+                    }
+                    (Err(err), ErrorPolicy::Strict) => return Err(err.into()),
+                    (Err(err), policy) => {
+                        if policy == ErrorPolicy::Collect {
+                            self.rejected_rows.push(line);
+                        }
+                        self.warnings.push(Warning::DroppedRow {
+                            line,
+                            reason: err.to_string(),
+                        });
+                    }
+                }
+                end = record_end;
+            }
+            match next {
+                Some(next) => pending = Some(next),
+                None => break,
+            }
+        }
+        let checkpoint = Checkpoint {
+            byte_offset: checkpoint.byte_offset + end,
+            records: checkpoint.records + records.len() as u64,
+        };
+        Ok((records, checkpoint))
+    }
+}
+
+/// A reader that remembers the last byte read from the underlying reader.
+struct LastByte<R> {
+    inner: R,
+    last: Option<u8>,
+}
+
+impl<R: Read> Read for LastByte<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        if len > 0 {
+            self.last = Some(buf[len - 1]);
+        }
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    #[test]
+    fn test_resume_csv_from_checkpoint() {
+        let path = std::env::temp_dir().join("readervzrd_resume_test.csv");
+        fs::write(&path, "a,b\n1,2\n3,").unwrap();
+        let mut reader = FileReader::new(path.to_str().unwrap(), Some(','))
+            .expect("Failed to create FileReader");
+        let (records, checkpoint) = reader.resume_from(&Checkpoint::default()).unwrap();
+        assert_eq!(records, vec![vec!["1", "2"]]);
+        assert_eq!(checkpoint.records(), 1);
+        assert_eq!(checkpoint.byte_offset(), 8);
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "4\n5,6\n").unwrap();
+        let mut reader = FileReader::new(path.to_str().unwrap(), Some(','))
+            .expect("Failed to create FileReader");
+        let (records, checkpoint) = reader.resume_from(&checkpoint).unwrap();
+        assert_eq!(records, vec![vec!["3", "4"], vec!["5", "6"]]);
+        assert_eq!(checkpoint.records(), 3);
+        assert_eq!(checkpoint.byte_offset(), fs::metadata(&path).unwrap().len());
+
+        let (records, _) = reader.resume_from(&checkpoint).unwrap();
+        assert!(records.is_empty());
+        assert_eq!(reader.records().unwrap().count(), 3);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_resume_csv_with_malformed_records() {
+        let mut reader = FileReader::new("tests/malformed_test.csv", Some(','))
+            .expect("Failed to create FileReader")
+            .error_policy(ErrorPolicy::Collect);
+        let (records, checkpoint) = reader.resume_from(&Checkpoint::default()).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(reader.rejected_rows(), [3]);
+        assert_eq!(
+            checkpoint.byte_offset(),
+            fs::metadata("tests/malformed_test.csv").unwrap().len()
+        );
+        let mut reader = FileReader::new("tests/malformed_test.csv", Some(','))
+            .expect("Failed to create FileReader")
+            .error_policy(ErrorPolicy::Strict);
+        assert!(matches!(
+            reader.resume_from(&Checkpoint::default()),
+            Err(FileError::CsvError(_))
+        ));
+    }
+
+    #[test]
+    fn test_resume_flexible_csv() {
+        let mut reader = FileReader::new("tests/dialect_test.tsv", Some('\t'))
+            .expect("Failed to create FileReader")
+            .comment(Some('#'))
+            .quote('\'')
+            .escape(Some('\\'))
+            .trim(true)
+            .flexible(true);
+        let (records, _) = reader.resume_from(&Checkpoint::default()).unwrap();
+        assert_eq!(
+            records,
+            vec![
+                vec!["1", "a'b", "x"],
+                vec!["2", "c", ""],
+                vec!["3", "d", "e"]
+            ]
+        );
+        assert_eq!(
+            reader.warnings(),
+            [Warning::ExtraColumns { line: 5, count: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_resume_json_from_checkpoint() {
+        let mut reader =
+            FileReader::new("tests/test.json", None).expect("Failed to create FileReader");
+        let checkpoint = Checkpoint {
+            byte_offset: 0,
+            records: 2,
+        };
+        let (records, checkpoint) = reader.resume_from(&checkpoint).unwrap();
+        assert_eq!(records, vec![vec!["40", "Canada", "Bob"]]);
+        assert_eq!(checkpoint.records(), 3);
+    }
+
+    #[test]
+    fn test_checkpoint_serialization() {
+        let checkpoint = Checkpoint {
+            byte_offset: 42,
+            records: 3,
+        };
+        let serialized = serde_json::to_string(&checkpoint).unwrap();
+        assert_eq!(serialized, r#"{"byte_offset":42,"records":3}"#);
+        assert_eq!(
+            serde_json::from_str::<Checkpoint>(&serialized).unwrap(),
+            checkpoint
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{ErrorPolicy, FileError, FileReader, RecordError};
+
+/// The differences between the records of two files, see [`FileReader::compare`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileDiff {
+    /// The columns of the file that are missing in the other file.
+    pub missing_columns: Vec<String>,
+    /// The columns of the other file that are missing in the file.
+    pub extra_columns: Vec<String>,
+    /// The records that differ in the shared columns, ordered by their number.
+    pub mismatched_rows: Vec<RowDiff>,
+    /// The malformed records of the file.
+    pub malformed_rows: Vec<MalformedRow>,
+    /// The malformed records of the other file.
+    pub other_malformed_rows: Vec<MalformedRow>,
+}
+
+impl FileDiff {
+    /// Returns whether both files have the same columns and records, apart from the order of
+    /// their columns.
+    pub fn is_empty(&self) -> bool {
+        self.missing_columns.is_empty()
+            && self.extra_columns.is_empty()
+            && self.mismatched_rows.is_empty()
+            && self.malformed_rows.is_empty()
+            && self.other_malformed_rows.is_empty()
+    }
+}
+
+/// A malformed record found by [`FileReader::compare`]. Malformed records are not compared,
+/// but keep their number, so that the following records are still compared with the records
+/// at the same position in the other file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedRow {
+    /// The number of the record, counted from 0.
+    pub row: usize,
+    /// The line of the file the record starts at.
+    pub line: u64,
+    /// The reason the record could not be read.
+    pub reason: String,
+}
+
+impl From<RecordError> for MalformedRow {
+    fn from(err: RecordError) -> MalformedRow {
+        MalformedRow {
+            row: err.record(),
+            line: err.line(),
+            reason: err.error().to_string(),
+        }
+    }
+}
+
+/// A record that differs between two files, see [`FileDiff`].
+/// The values are those of the shared columns in the column order of the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowDiff {
+    /// The number of the record in both files, counted from 0.
+    pub row: usize,
+    /// The shared columns whose values differ. All shared columns differ if the record is
+    /// missing in one of the files.
+    pub columns: Vec<String>,
+    /// The values of the record in the file, or `None` if the file has fewer records.
+    pub values: Option<Vec<String>>,
+    /// The values of the record in the other file, or `None` if it has fewer records.
+    pub other_values: Option<Vec<String>>,
+}
+
+impl FileReader {
+    /// Compares the headers and records of the file with another file, which may be in a
+    /// different format. Columns are matched by their headers, so their order does not matter,
+    /// and records are compared by their number, counting malformed records regardless of the
+    /// [`ErrorPolicy`]. Values are compared as they are returned by
+    /// [`FileReader::records`], so headers can be aligned with [`FileReader::header_map`] and
+    /// missing values with [`FileReader::null_output`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{FileReader, HeaderTransform};
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(','))
+    ///     .expect("Failed to create FileReader")
+    ///     .rename_headers(HeaderTransform::Lowercase);
+    /// let mut other = FileReader::new("tests/test.json", None).expect("Failed to create FileReader");
+    /// assert!(reader.compare(&mut other).unwrap().is_empty());
+    /// ```
+    pub fn compare(&mut self, other: &mut FileReader) -> Result<FileDiff, FileError> {
+        let policies = (self.error_policy, other.error_policy);
+        self.error_policy = ErrorPolicy::Strict;
+        other.error_policy = ErrorPolicy::Strict;
+        let diff = self.compare_records(other);
+        (self.error_policy, other.error_policy) = policies;
+        diff
+    }
+
+    fn compare_records(&mut self, other: &mut FileReader) -> Result<FileDiff, FileError> {
+        let headers = self.headers()?;
+        let other_headers = other.headers()?;
+        let shared: Vec<(usize, usize)> = headers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, header)| {
+                other_headers
+                    .iter()
+                    .position(|other_header| other_header == header)
+                    .map(|other_index| (index, other_index))
+            })
+            .collect();
+        let mut diff = FileDiff {
+            missing_columns: headers
+                .iter()
+                .filter(|header| !other_headers.contains(header))
+                .cloned()
+                .collect(),
+            extra_columns: other_headers
+                .iter()
+                .filter(|header| !headers.contains(header))
+                .cloned()
+                .collect(),
+            mismatched_rows: Vec::new(),
+            malformed_rows: Vec::new(),
+            other_malformed_rows: Vec::new(),
+        };
+        let shared_values = |record: Vec<String>, other: bool| -> Vec<String> {
+            shared
+                .iter()
+                .map(|&(index, other_index)| {
+                    let index = if other { other_index } else { index };
+                    record.get(index).cloned().unwrap_or_default()
+                })
+                .collect()
+        };
+        let mut records = self.try_records()?;
+        let mut other_records = other.try_records()?;
+        for row in 0.. {
+            let (record, other_record) = match (records.next(), other_records.next()) {
+                (None, None) => break,
+                pair => pair,
+            };
+            let (values, other_values) = match (record.transpose(), other_record.transpose()) {
+                (Ok(record), Ok(other_record)) => (
+                    record.map(|record| shared_values(record, false)),
+                    other_record.map(|record| shared_values(record, true)),
+                ),
+                (record, other_record) => {
+                    if let Err(err) = record {
+                        diff.malformed_rows.push(err.into());
+                    }
+                    if let Err(err) = other_record {
+                        diff.other_malformed_rows.push(err.into());
+                    }
+                    continue;
+                }
+            };
+            let columns: Vec<String> = shared
+                .iter()
+                .enumerate()
+                .filter(|&(position, _)| match (&values, &other_values) {
+                    (Some(values), Some(other_values)) => {
+                        values[position] != other_values[position]
+                    }
+                    _ => true,
+                })
+                .map(|(_, &(index, _))| headers[index].clone())
+                .collect();
+            if !columns.is_empty() || values.is_none() || other_values.is_none() {
+                diff.mismatched_rows.push(RowDiff {
+                    row,
+                    columns,
+                    values,
+                    other_values,
+                });
+            }
+        }
+        Ok(diff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_files() {
+        let mut reader = FileReader::new("tests/test.csv", Some(','))
+            .expect("Failed to create FileReader")
+            .header_map(&[("Name", "name"), ("Age", "age")]);
+        let mut other = FileReader::new("tests/malformed_test.csv", Some(','))
+            .expect("Failed to create FileReader");
+        let diff = reader.compare(&mut other).unwrap();
+        assert_eq!(diff.missing_columns, vec!["name", "age"]);
+        assert_eq!(diff.extra_columns, vec!["Name", "Age"]);
+        assert!(diff.mismatched_rows.is_empty());
+        assert!(diff.malformed_rows.is_empty());
+        assert_eq!(
+            diff.other_malformed_rows,
+            vec![MalformedRow {
+                row: 1,
+                line: 3,
+                reason: diff.other_malformed_rows[0].reason.clone(),
+            }]
+        );
+        assert!(!diff.is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A compression scheme that is transparently decompressed when reading files.
+/// Implement this trait to read files compressed with custom schemes and pass the
+/// implementation to [`crate::FileReader::with_decompressors`].
+///
+/// # Examples
+///
+/// ```
+/// use readervzrd::Decompressor;
+/// use std::io::{self, Read};
+///
+/// /// Files stored with a trivial XOR "compression".
+/// struct Xor;
+///
+/// struct XorReader(Box<dyn Read + Send>);
+///
+/// impl Read for XorReader {
+///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+///         let len = self.0.read(buf)?;
+///         buf[..len].iter_mut().for_each(|b| *b ^= 0x2a);
+///         Ok(len)
+///     }
+/// }
+///
+/// impl Decompressor for Xor {
+///     fn extension(&self) -> &str {
+///         "xor"
+///     }
+///
+///     fn decompress(&self, reader: Box<dyn Read + Send>) -> io::Result<Box<dyn Read + Send>> {
+///         Ok(Box::new(XorReader(reader)))
+///     }
+/// }
+/// ```
+pub trait Decompressor: Send {
+    /// Returns the file extension of files compressed with this scheme, e.g. `gz`.
+    fn extension(&self) -> &str;
+
+    /// Wraps the given compressed input into a reader over the decompressed data.
+    fn decompress(&self, reader: Box<dyn Read + Send>) -> io::Result<Box<dyn Read + Send>>;
+}
+
+/// The gzip compression scheme, used for files ending with `.gz`.
+pub struct Gzip;
+
+impl Decompressor for Gzip {
+    fn extension(&self) -> &str {
+        "gz"
+    }
+
+    fn decompress(&self, reader: Box<dyn Read + Send>) -> io::Result<Box<dyn Read + Send>> {
+        Ok(Box::new(flate2::read::MultiGzDecoder::new(reader)))
+    }
+}
+
+/// Returns the decompressors that are applied to files with matching extensions by default.
+pub(crate) fn default_decompressors() -> Vec<Box<dyn Decompressor>> {
+    vec![Box::new(Gzip)]
+}
+
+/// Strips the extensions of all compression schemes from the given path, outermost first,
+/// and returns the remaining path together with the matching decompressors.
+pub(crate) fn detect_compression(
+    file_path: &str,
+    mut decompressors: Vec<Box<dyn Decompressor>>,
+) -> (&str, Vec<Box<dyn Decompressor>>) {
+    let mut path = file_path;
+    let mut stack = Vec::new();
+    loop {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str());
+        let Some(index) = decompressors
+            .iter()
+            .position(|decompressor| Some(decompressor.extension()) == extension)
+        else {
+            return (path, stack);
+        };
+        path = &path[..path.len() - decompressors[index].extension().len() - 1];
+        stack.push(decompressors.remove(index));
+    }
+}
+
+/// Opens an input that cannot seek, like a member of an archive, at its start.
+pub(crate) type Opener = Arc<dyn Fn() -> io::Result<Box<dyn Read + Send>> + Send + Sync>;
+
+/// A seekable view on the decompressed contents of a file or another input. Seeking backwards
+/// reopens and decompresses the input from its start, seeking forwards skips the decompressed
+/// data.
+pub(crate) struct DecompressedSource {
+    open: Opener,
+    decompressors: Vec<Box<dyn Decompressor>>,
+    reader: Box<dyn Read + Send>,
+    position: u64,
+}
+
+impl DecompressedSource {
+    pub(crate) fn new(
+        path: PathBuf,
+        decompressors: Vec<Box<dyn Decompressor>>,
+    ) -> io::Result<DecompressedSource> {
+        DecompressedSource::from_opener(
+            Arc::new(move || Ok(Box::new(File::open(&path)?))),
+            decompressors,
+        )
+    }
+
+    pub(crate) fn from_opener(
+        open: Opener,
+        decompressors: Vec<Box<dyn Decompressor>>,
+    ) -> io::Result<DecompressedSource> {
+        let reader = decompress(open()?, &decompressors)?;
+        Ok(DecompressedSource {
+            open,
+            decompressors,
+            reader,
+            position: 0,
+        })
+    }
+}
+
+fn decompress(
+    mut reader: Box<dyn Read + Send>,
+    decompressors: &[Box<dyn Decompressor>],
+) -> io::Result<Box<dyn Read + Send>> {
+    for decompressor in decompressors {
+        reader = decompressor.decompress(reader)?;
+    }
+    Ok(reader)
+}
+
+impl Read for DecompressedSource {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.reader.read(buf)?;
+        self.position += len as u64;
+        Ok(len)
+    }
+}
+
+impl Seek for DecompressedSource {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => offset,
+            SeekFrom::Current(offset) => self
+                .position
+                .checked_add_signed(offset)
+                .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?,
+            SeekFrom::End(_) => return Err(io::Error::from(io::ErrorKind::Unsupported)),
+        };
+        if target < self.position {
+            self.reader = decompress((self.open)()?, &self.decompressors)?;
+            self.position = 0;
+        }
+        let skipped = io::copy(
+            &mut self.reader.by_ref().take(target - self.position),
+            &mut io::sink(),
+        )?;
+        self.position += skipped;
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Identity(&'static str);
+
+    impl Decompressor for Identity {
+        fn extension(&self) -> &str {
+            self.0
+        }
+
+        fn decompress(&self, reader: Box<dyn Read + Send>) -> io::Result<Box<dyn Read + Send>> {
+            Ok(reader)
+        }
+    }
+
+    #[test]
+    fn test_detect_compression() {
+        let (path, stack) = detect_compression("data.csv.gz", default_decompressors());
+        assert_eq!(path, "data.csv");
+        assert_eq!(stack.len(), 1);
+
+        let decompressors: Vec<Box<dyn Decompressor>> =
+            vec![Box::new(Gzip), Box::new(Identity("enc"))];
+        let (path, stack) = detect_compression("data.json.gz.enc", decompressors);
+        assert_eq!(path, "data.json");
+        assert_eq!(
+            stack.iter().map(|d| d.extension()).collect::<Vec<_>>(),
+            vec!["enc", "gz"]
+        );
+
+        let (path, stack) = detect_compression("data.csv", default_decompressors());
+        assert_eq!(path, "data.csv");
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn test_seek_decompressed_source() {
+        let mut source =
+            DecompressedSource::new(PathBuf::from("tests/test.csv.gz"), default_decompressors())
+                .unwrap();
+        let mut header = String::new();
+        source
+            .by_ref()
+            .take(16)
+            .read_to_string(&mut header)
+            .unwrap();
+        assert_eq!(header, "Name,Age,Country");
+        assert_eq!(source.seek(SeekFrom::Start(17)).unwrap(), 17);
+        let mut name = String::new();
+        source.by_ref().take(4).read_to_string(&mut name).unwrap();
+        assert_eq!(name, "John");
+        assert_eq!(source.seek(SeekFrom::Current(-8)).unwrap(), 13);
+        assert_eq!(source.seek(SeekFrom::Start(0)).unwrap(), 0);
+        assert!(source.seek(SeekFrom::End(0)).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{FileError, FileReader};
+use serde::de::DeserializeOwned;
+
+impl FileReader {
+    /// Returns an iterator over the records of the file deserialized into the given type.
+    /// The headers are used as field names, so nested JSON fields have to be renamed to
+    /// their dotted header, e.g. `#[serde(rename = "bank.account")]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Person {
+    ///     #[serde(rename = "Name")]
+    ///     name: String,
+    ///     #[serde(rename = "Age")]
+    ///     age: u32,
+    /// }
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// for person in reader.records_as::<Person>().unwrap() {
+    ///     let person = person.expect("Failed to deserialize record");
+    ///     println!("{} is {} years old", person.name, person.age);
+    /// }
+    /// ```
+    pub fn records_as<T: DeserializeOwned>(
+        &mut self,
+    ) -> Result<impl Iterator<Item = Result<T, FileError>> + '_, FileError> {
+        let headers = csv::StringRecord::from(self.headers()?);
+        Ok(self
+            .records()?
+            .enumerate()
+            .map(move |(row, record)| deserialize_record(&headers, row, record)))
+    }
+}
+
+fn deserialize_record<T: DeserializeOwned>(
+    headers: &csv::StringRecord,
+    row: usize,
+    record: Vec<String>,
+) -> Result<T, FileError> {
+    csv::StringRecord::from(record)
+        .deserialize(Some(headers))
+        .map_err(|err| match err.kind() {
+            csv::ErrorKind::Deserialize { err: source, .. } => FileError::DeserializeError {
+                row,
+                source: source.clone(),
+            },
+            _ => FileError::CsvError(err),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Person {
+        name: String,
+        age: u32,
+        #[serde(rename = "bank.institution")]
+        bank: Option<String>,
+    }
+
+    #[test]
+    fn test_json_records_as() {
+        let mut reader =
+            FileReader::new("tests/nested_test.json", None).expect("Failed to create FileReader");
+        let persons: Vec<Person> = reader
+            .records_as()
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .expect("Failed to deserialize records");
+        assert_eq!(persons.len(), 3);
+        assert_eq!(
+            persons[0],
+            Person {
+                name: "John".to_string(),
+                age: 30,
+                bank: Some("Chase".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_records_as_with_type_error() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Invalid {
+            #[serde(rename = "Country")]
+            country: u32,
+        }
+
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        let result = reader.records_as::<Invalid>().unwrap().next().unwrap();
+        assert!(matches!(
+            result,
+            Err(FileError::DeserializeError { row: 0, .. })
+        ));
+    }
+}
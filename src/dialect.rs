@@ -0,0 +1,155 @@
+use crate::FileReader;
+
+/// The options of reading CSV files beyond their delimiter, see [`FileReader::comment`],
+/// [`FileReader::quote`], [`FileReader::escape`], [`FileReader::trim`] and
+/// [`FileReader::flexible`].
+#[derive(Debug, Clone)]
+pub(crate) struct CsvDialect {
+    comment: Option<char>,
+    quote: char,
+    escape: Option<char>,
+    trim: bool,
+    pub(crate) flexible: bool,
+    /// Whether the options are kept at their defaults, because the CSV data has been written
+    /// by the reader itself, like the attribute tables of shapefiles.
+    pub(crate) fixed: bool,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        CsvDialect {
+            comment: None,
+            quote: '"',
+            escape: None,
+            trim: false,
+            flexible: false,
+            fixed: false,
+        }
+    }
+}
+
+/// Returns a CSV reader builder for the given delimiter and dialect.
+pub(crate) fn csv_reader_builder(delimiter: char, dialect: &CsvDialect) -> csv::ReaderBuilder {
+    let mut builder = csv::ReaderBuilder::new();
+    builder
+        .delimiter(delimiter as u8)
+        .comment(dialect.comment.map(|comment| comment as u8))
+        .quote(dialect.quote as u8)
+        .escape(dialect.escape.map(|escape| escape as u8))
+        .trim(if dialect.trim {
+            csv::Trim::All
+        } else {
+            csv::Trim::None
+        })
+        .flexible(dialect.flexible);
+    builder
+}
+
+impl FileReader {
+    /// Sets the character that starts comment lines in CSV files, which are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/variants.tsv", Some('\t'))
+    ///     .expect("Failed to create FileReader")
+    ///     .comment(Some('#'));
+    /// assert_eq!(reader.headers().unwrap(), vec!["chrom", "pos", "qual"]);
+    /// ```
+    pub fn comment(self, comment: Option<char>) -> FileReader {
+        self.with_dialect(|dialect| dialect.comment = comment)
+    }
+
+    /// Sets the character that quotes fields in CSV files. Defaults to `"`.
+    pub fn quote(self, quote: char) -> FileReader {
+        self.with_dialect(|dialect| dialect.quote = quote)
+    }
+
+    /// Sets the character that escapes quotes within quoted fields of CSV files, like `\`.
+    /// Quotes are always escaped by doubling them as well.
+    pub fn escape(self, escape: Option<char>) -> FileReader {
+        self.with_dialect(|dialect| dialect.escape = escape)
+    }
+
+    /// Sets whether leading and trailing whitespace is removed from the headers and fields of
+    /// CSV files.
+    pub fn trim(self, trim: bool) -> FileReader {
+        self.with_dialect(|dialect| dialect.trim = trim)
+    }
+
+    /// Sets whether CSV records with a different number of fields than the header are
+    /// accepted. Missing fields are filled with empty values and extra fields are left out,
+    /// which is reported by [`FileReader::warnings`]. Otherwise, such records are malformed
+    /// and handled according to the [`ErrorPolicy`](crate::ErrorPolicy).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/dialect_test.tsv", Some('\t'))
+    ///     .expect("Failed to create FileReader")
+    ///     .comment(Some('#'))
+    ///     .quote('\'')
+    ///     .escape(Some('\\'))
+    ///     .trim(true)
+    ///     .flexible(true);
+    /// let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+    /// assert_eq!(records[0], vec!["1", "a'b", "x"]);
+    /// assert_eq!(records[1], vec!["2", "c", ""]);
+    /// ```
+    pub fn flexible(self, flexible: bool) -> FileReader {
+        self.with_dialect(|dialect| dialect.flexible = flexible)
+    }
+
+    /// Changes the CSV dialect unless it is fixed.
+    fn with_dialect(mut self, change: impl FnOnce(&mut CsvDialect)) -> FileReader {
+        if !self.csv_dialect.fixed {
+            change(&mut self.csv_dialect);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorPolicy, Warning};
+
+    fn dialect_reader() -> FileReader {
+        FileReader::new("tests/dialect_test.tsv", Some('\t'))
+            .expect("Failed to create FileReader")
+            .comment(Some('#'))
+            .quote('\'')
+            .escape(Some('\\'))
+            .trim(true)
+    }
+
+    #[test]
+    fn test_flexible_records() {
+        let mut reader = dialect_reader().flexible(true);
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(
+            records,
+            vec![
+                vec!["1", "a'b", "x"],
+                vec!["2", "c", ""],
+                vec!["3", "d", "e"]
+            ]
+        );
+        assert_eq!(
+            reader.warnings(),
+            [Warning::ExtraColumns { line: 5, count: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_inflexible_records() {
+        let mut reader = dialect_reader().error_policy(ErrorPolicy::Collect);
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records, vec![vec!["1", "a'b", "x"]]);
+        assert_eq!(reader.rejected_rows(), [4, 5]);
+    }
+}
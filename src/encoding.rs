@@ -0,0 +1,221 @@
+use crate::Source;
+use encoding_rs::{CoderResult, Decoder, Encoding, UTF_8};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// A source that transcodes its input to UTF-8 on the fly.
+/// Without a configured encoding and BOM detection the input is passed through unchanged.
+pub(crate) struct Transcoder {
+    inner: Box<dyn Source>,
+    encoding: Option<&'static Encoding>,
+    detect_bom: bool,
+    decoder: Option<Decoder>,
+    input: Vec<u8>,
+    input_range: (usize, usize),
+    input_finished: bool,
+    output: Vec<u8>,
+    output_range: (usize, usize),
+    position: u64,
+    lossy: Arc<AtomicBool>,
+    input_position: Arc<AtomicU64>,
+}
+
+impl Transcoder {
+    pub(crate) fn new(inner: Box<dyn Source>) -> Transcoder {
+        Transcoder {
+            inner,
+            encoding: None,
+            detect_bom: false,
+            decoder: None,
+            input: vec![0; BUFFER_SIZE],
+            input_range: (0, 0),
+            input_finished: false,
+            output: vec![0; BUFFER_SIZE],
+            output_range: (0, 0),
+            position: 0,
+            lossy: Arc::new(AtomicBool::new(false)),
+            input_position: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Sets the encoding of the input and whether a byte order mark determines the encoding
+    /// instead. Resets the transcoder to the start of the input.
+    pub(crate) fn configure(
+        &mut self,
+        encoding: Option<&'static Encoding>,
+        detect_bom: bool,
+    ) -> io::Result<()> {
+        self.encoding = encoding;
+        self.detect_bom = detect_bom;
+        self.reset()
+    }
+
+    pub(crate) fn encoding(&self) -> Option<&'static Encoding> {
+        self.encoding
+    }
+
+    pub(crate) fn detect_bom(&self) -> bool {
+        self.detect_bom
+    }
+
+    /// Returns a flag that is set once malformed input has been replaced with U+FFFD since
+    /// the last reset.
+    pub(crate) fn lossy(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.lossy)
+    }
+
+    /// Returns a counter of the bytes of the input that have been read, which differs from the
+    /// position in the transcoded output for other encodings than UTF-8.
+    pub(crate) fn input_position(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.input_position)
+    }
+
+    /// Returns the length of the input if its source supports seeking to its end.
+    /// Resets the transcoder to the start of the input.
+    pub(crate) fn input_len(&mut self) -> io::Result<Option<u64>> {
+        let len = self.inner.seek(SeekFrom::End(0)).ok();
+        self.reset()?;
+        Ok(len)
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        self.inner.seek(SeekFrom::Start(0))?;
+        self.decoder = match (self.encoding, self.detect_bom) {
+            (None, false) => None,
+            (encoding, true) => Some(encoding.unwrap_or(UTF_8).new_decoder()),
+            (Some(encoding), false) => Some(encoding.new_decoder_with_bom_removal()),
+        };
+        self.input_range = (0, 0);
+        self.input_finished = false;
+        self.output_range = (0, 0);
+        self.position = 0;
+        self.lossy.store(false, Ordering::Relaxed);
+        self.input_position.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Decodes the next chunk of input into the output buffer.
+    fn fill_output(&mut self, decoder: &mut Decoder) -> io::Result<()> {
+        while self.output_range.0 == self.output_range.1 {
+            if self.input_range.0 == self.input_range.1 && !self.input_finished {
+                let len = self.inner.read(&mut self.input)?;
+                self.input_position.fetch_add(len as u64, Ordering::Relaxed);
+                self.input_range = (0, len);
+                self.input_finished = len == 0;
+            }
+            let input = &self.input[self.input_range.0..self.input_range.1];
+            let (result, read, written, replaced) =
+                decoder.decode_to_utf8(input, &mut self.output, self.input_finished);
+            if replaced {
+                self.lossy.store(true, Ordering::Relaxed);
+            }
+            self.input_range.0 += read;
+            self.output_range = (0, written);
+            if self.input_finished && result == CoderResult::InputEmpty {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Read for Transcoder {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(mut decoder) = self.decoder.take() else {
+            let len = self.inner.read(buf)?;
+            self.position += len as u64;
+            self.input_position.store(self.position, Ordering::Relaxed);
+            return Ok(len);
+        };
+        let result = self.fill_output(&mut decoder);
+        self.decoder = Some(decoder);
+        result?;
+        let available = &self.output[self.output_range.0..self.output_range.1];
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.output_range.0 += len;
+        self.position += len as u64;
+        Ok(len)
+    }
+}
+
+impl Seek for Transcoder {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        if self.decoder.is_none() {
+            self.position = self.inner.seek(pos)?;
+            self.input_position.store(self.position, Ordering::Relaxed);
+            return Ok(self.position);
+        }
+        let target = match pos {
+            SeekFrom::Start(offset) => offset,
+            SeekFrom::Current(offset) => self
+                .position
+                .checked_add_signed(offset)
+                .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?,
+            SeekFrom::End(_) => return Err(io::Error::from(io::ErrorKind::Unsupported)),
+        };
+        if target < self.position {
+            self.reset()?;
+        }
+        let remaining = target - self.position;
+        io::copy(&mut self.by_ref().take(remaining), &mut io::sink())?;
+        Ok(self.position)
+    }
+}
+
+/// Returns the encoding for the given label, e.g. `latin1` or `windows-1252`.
+pub(crate) fn encoding_for_label(label: &str) -> Option<&'static Encoding> {
+    Encoding::for_label(label.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcode(data: &[u8], encoding: Option<&'static Encoding>, detect_bom: bool) -> String {
+        let mut transcoder = Transcoder::new(Box::new(io::Cursor::new(data.to_vec())));
+        transcoder.configure(encoding, detect_bom).unwrap();
+        let mut output = String::new();
+        transcoder.read_to_string(&mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn test_transcode() {
+        let latin1 = b"J\xfcrgen";
+        assert_eq!(
+            transcode(latin1, encoding_for_label("latin1"), false),
+            "Jürgen"
+        );
+        assert_eq!(transcode(b"\xef\xbb\xbfName", None, true), "Name");
+        assert_eq!(
+            transcode(b"\xff\xfeN\x00a\x00", encoding_for_label("latin1"), true),
+            "Na"
+        );
+        assert_eq!(transcode(b"\xef\xbb\xbfName", None, false), "\u{feff}Name");
+    }
+
+    #[test]
+    fn test_seek_transcoder() {
+        let data = "Jürgen,München\n".repeat(2000);
+        let latin1 = encoding_rs::WINDOWS_1252.encode(&data).0.into_owned();
+        let mut transcoder = Transcoder::new(Box::new(io::Cursor::new(latin1)));
+        transcoder
+            .configure(encoding_for_label("latin1"), false)
+            .unwrap();
+        let mut output = String::new();
+        transcoder.read_to_string(&mut output).unwrap();
+        assert_eq!(output, data);
+        assert_eq!(transcoder.seek(SeekFrom::Start(8)).unwrap(), 8);
+        let mut city = String::new();
+        transcoder
+            .by_ref()
+            .take(8)
+            .read_to_string(&mut city)
+            .unwrap();
+        assert_eq!(city, "München");
+    }
+}
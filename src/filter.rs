@@ -0,0 +1,185 @@
+use crate::{FileError, FileReader};
+use std::str::FromStr;
+
+/// A simple predicate on a single column, e.g. `Country == USA` or `Age >= 30`.
+/// Equality operators compare the text of the field, ordering operators compare numerically
+/// and never match fields that are not numeric. Values may be enclosed in double quotes.
+///
+/// # Examples
+///
+/// ```
+/// use readervzrd::{FileReader, Filter};
+///
+/// let filter: Filter = "Age > 28".parse().expect("Invalid filter");
+/// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+/// let records: Vec<Vec<String>> = reader
+///     .records_where(|headers, record| filter.matches(headers, record))
+///     .unwrap()
+///     .collect();
+/// assert_eq!(records.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    column: String,
+    operator: Operator,
+    value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Operators in the order they are matched, longer ones first.
+const OPERATORS: [(&str, Operator); 6] = [
+    ("==", Operator::Eq),
+    ("!=", Operator::Ne),
+    ("<=", Operator::Le),
+    (">=", Operator::Ge),
+    ("<", Operator::Lt),
+    (">", Operator::Gt),
+];
+
+impl Filter {
+    /// Returns whether the given record matches the filter.
+    /// Records without the filtered column never match.
+    pub fn matches(&self, headers: &[String], record: &[String]) -> bool {
+        let Some(field) = headers
+            .iter()
+            .position(|header| *header == self.column)
+            .and_then(|index| record.get(index))
+        else {
+            return false;
+        };
+        match self.operator {
+            Operator::Eq => *field == self.value,
+            Operator::Ne => *field != self.value,
+            operator => match (field.trim().parse::<f64>(), self.value.parse::<f64>()) {
+                (Ok(field), Ok(value)) => match operator {
+                    Operator::Lt => field < value,
+                    Operator::Le => field <= value,
+                    Operator::Gt => field > value,
+                    _ => field >= value,
+                },
+                _ => false,
+            },
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = FileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (index, symbol, operator) = s
+            .char_indices()
+            .find_map(|(index, _)| {
+                OPERATORS
+                    .iter()
+                    .find(|(symbol, _)| s[index..].starts_with(symbol))
+                    .map(|(symbol, operator)| (index, *symbol, *operator))
+            })
+            .ok_or_else(|| FileError::InvalidFilter(s.to_string()))?;
+        let column = s[..index].trim();
+        let value = s[index + symbol.len()..].trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+        if column.is_empty() {
+            return Err(FileError::InvalidFilter(s.to_string()));
+        }
+        Ok(Filter {
+            column: column.to_string(),
+            operator,
+            value: value.to_string(),
+        })
+    }
+}
+
+impl FileReader {
+    /// Returns an iterator over the records of the file for which the given predicate returns
+    /// true. The predicate is called with the headers and the record during iteration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let records: Vec<Vec<String>> = reader
+    ///     .records_where(|_, record| record[2] == "USA")
+    ///     .unwrap()
+    ///     .collect();
+    /// assert_eq!(records, vec![vec!["John", "30", "USA"]]);
+    /// ```
+    pub fn records_where<'a, F>(
+        &'a mut self,
+        mut predicate: F,
+    ) -> Result<impl Iterator<Item = Vec<String>> + 'a, FileError>
+    where
+        F: FnMut(&[String], &[String]) -> bool + 'a,
+    {
+        let headers = self.headers()?;
+        Ok(self
+            .records()?
+            .filter(move |record| predicate(&headers, record)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(
+            "Country == \"United Kingdom\"".parse::<Filter>().unwrap(),
+            Filter {
+                column: "Country".to_string(),
+                operator: Operator::Eq,
+                value: "United Kingdom".to_string(),
+            }
+        );
+        assert_eq!("Age<=30".parse::<Filter>().unwrap().operator, Operator::Le);
+        assert_eq!(
+            "Age".parse::<Filter>(),
+            Err(FileError::InvalidFilter("Age".to_string()))
+        );
+        assert_eq!(
+            "== 30".parse::<Filter>(),
+            Err(FileError::InvalidFilter("== 30".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_records_where_filter() {
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        let filter: Filter = "Age < 30".parse().unwrap();
+        let records: Vec<Vec<String>> = reader
+            .records_where(|headers, record| filter.matches(headers, record))
+            .unwrap()
+            .collect();
+        assert_eq!(records, vec![vec!["Alice", "25", "UK"]]);
+        let filter: Filter = "Name != John".parse().unwrap();
+        let records: Vec<Vec<String>> = reader
+            .records_where(|headers, record| filter.matches(headers, record))
+            .unwrap()
+            .collect();
+        assert_eq!(records.len(), 2);
+    }
+
+    #[test]
+    fn test_filter_non_numeric() {
+        let filter: Filter = "Name > 3".parse().unwrap();
+        let headers = vec!["Name".to_string()];
+        assert!(!filter.matches(&headers, &["John".to_string()]));
+        assert!(!filter.matches(&["Other".to_string()], &["5".to_string()]));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::FileError;
+use serde_json::{Map, Value};
+
+/// Header of the column containing the geometry of a feature.
+const GEOMETRY: &str = "geometry";
+
+/// Extracts the features of a GeoJSON `FeatureCollection` or a single `Feature` as a table.
+/// Nested properties are flattened into dotted headers like JSON objects and the geometry of
+/// each feature is serialized as WKT into a trailing `geometry` column. The first row contains
+/// the headers in order of first appearance. Null and absent properties and null geometries
+/// are replaced with the given placeholder.
+pub(crate) fn read_geojson_table(text: &str, null: &str) -> Result<Vec<Vec<String>>, FileError> {
+    let document: Value = serde_json::from_str(text)?;
+    let features = match document.get("type").and_then(Value::as_str) {
+        Some("FeatureCollection") => match document.get("features") {
+            Some(Value::Array(features)) => features.iter().collect(),
+            _ => return Err(FileError::InvalidJsonStructure),
+        },
+        Some("Feature") => vec![&document],
+        _ => return Err(FileError::InvalidJsonStructure),
+    };
+    let mut rows = Vec::new();
+    for feature in features {
+        let mut fields = Vec::new();
+        match feature.get("properties") {
+            Some(Value::Object(properties)) => flatten(&mut fields, properties, "", null),
+            Some(Value::Null) | None => {}
+            Some(_) => return Err(FileError::InvalidJsonStructure),
+        }
+        let geometry = match feature.get(GEOMETRY) {
+            Some(Value::Null) | None => null.to_string(),
+            Some(geometry) => wkt(geometry)?,
+        };
+        fields.push((GEOMETRY.to_string(), geometry));
+        rows.push(fields);
+    }
+    let mut headers: Vec<String> = Vec::new();
+    for (key, _) in rows.iter().flatten() {
+        if !headers.contains(key) {
+            headers.push(key.clone());
+        }
+    }
+    let records = rows.into_iter().map(|fields| {
+        headers
+            .iter()
+            .map(|header| {
+                fields
+                    .iter()
+                    .find(|(key, _)| key == header)
+                    .map_or_else(|| null.to_string(), |(_, value)| value.clone())
+            })
+            .collect()
+    });
+    Ok(std::iter::once(headers.clone()).chain(records).collect())
+}
+
+/// Flattens the given properties into pairs of dotted key and value.
+fn flatten(
+    fields: &mut Vec<(String, String)>,
+    properties: &Map<String, Value>,
+    prefix: &str,
+    null: &str,
+) {
+    for (key, value) in properties {
+        let key = if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Object(inner) => flatten(fields, inner, &key, null),
+            Value::Null => fields.push((key, null.to_string())),
+            Value::String(s) => fields.push((key, s.to_string())),
+            value => fields.push((key, value.to_string())),
+        }
+    }
+}
+
+/// Serializes a GeoJSON geometry as well-known text.
+fn wkt(geometry: &Value) -> Result<String, FileError> {
+    let kind = geometry
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or(FileError::InvalidJsonStructure)?;
+    if kind == "GeometryCollection" {
+        let geometries = geometry
+            .get("geometries")
+            .and_then(Value::as_array)
+            .ok_or(FileError::InvalidJsonStructure)?;
+        if geometries.is_empty() {
+            return Ok("GEOMETRYCOLLECTION EMPTY".to_string());
+        }
+        let geometries = geometries.iter().map(wkt).collect::<Result<Vec<_>, _>>()?;
+        return Ok(format!("GEOMETRYCOLLECTION ({})", geometries.join(", ")));
+    }
+    let depth = match kind {
+        "Point" => 0,
+        "LineString" | "MultiPoint" => 1,
+        "Polygon" | "MultiLineString" => 2,
+        "MultiPolygon" => 3,
+        _ => return Err(FileError::InvalidJsonStructure),
+    };
+    let coordinates = geometry
+        .get("coordinates")
+        .ok_or(FileError::InvalidJsonStructure)?;
+    if coordinates.as_array().is_some_and(Vec::is_empty) {
+        return Ok(format!("{} EMPTY", kind.to_uppercase()));
+    }
+    Ok(format!(
+        "{} {}",
+        kind.to_uppercase(),
+        coordinate_text(coordinates, depth, true)?
+    ))
+}
+
+/// Serializes nested coordinate arrays of the given depth, where depth 0 is a single position.
+/// Positions are wrapped in parentheses only at the top level, i.e. for points.
+fn coordinate_text(coordinates: &Value, depth: usize, top: bool) -> Result<String, FileError> {
+    let items = coordinates
+        .as_array()
+        .ok_or(FileError::InvalidJsonStructure)?;
+    if depth == 0 {
+        let position = items
+            .iter()
+            .map(|n| n.as_number().map(|n| n.to_string()))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(FileError::InvalidJsonStructure)?
+            .join(" ");
+        return Ok(if top {
+            format!("({})", position)
+        } else {
+            position
+        });
+    }
+    let items = items
+        .iter()
+        .map(|item| coordinate_text(item, depth - 1, false))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("({})", items.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_read_geojson_table() {
+        let text = std::fs::read_to_string("tests/test.geojson").unwrap();
+        assert_eq!(
+            read_geojson_table(&text, "").unwrap(),
+            vec![
+                vec!["address.city", "capital", "name", "population", "geometry"],
+                vec![
+                    "Berlin",
+                    "true",
+                    "Berlin",
+                    "3850809",
+                    "POINT (13.405 52.52)"
+                ],
+                vec![
+                    "",
+                    "",
+                    "Spree",
+                    "",
+                    "LINESTRING (13.2 52.5, 13.4 52.51, 13.5 52.48)"
+                ],
+                vec!["", "", "Unknown", "", ""],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wkt() {
+        let polygon = json!({
+            "type": "Polygon",
+            "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 0]]]
+        });
+        assert_eq!(wkt(&polygon).unwrap(), "POLYGON ((0 0, 1 0, 1 1, 0 0))");
+        let multipoint = json!({"type": "MultiPoint", "coordinates": [[0, 0], [1.5, 2]]});
+        assert_eq!(wkt(&multipoint).unwrap(), "MULTIPOINT (0 0, 1.5 2)");
+        let collection = json!({
+            "type": "GeometryCollection",
+            "geometries": [{"type": "Point", "coordinates": [1, 2]}]
+        });
+        assert_eq!(
+            wkt(&collection).unwrap(),
+            "GEOMETRYCOLLECTION (POINT (1 2))"
+        );
+        let empty = json!({"type": "LineString", "coordinates": []});
+        assert_eq!(wkt(&empty).unwrap(), "LINESTRING EMPTY");
+    }
+
+    #[test]
+    fn test_invalid_geojson() {
+        assert_eq!(
+            read_geojson_table(r#"{"type": "Point", "coordinates": [1, 2]}"#, ""),
+            Err(FileError::InvalidJsonStructure)
+        );
+    }
+}
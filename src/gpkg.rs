@@ -0,0 +1,256 @@
+use crate::wkt::{Geometry, Position};
+use crate::FileError;
+use rusqlite::Connection;
+
+/// Returns whether a SQLite database is a GeoPackage, i.e. has a table of geometry columns.
+pub(crate) fn is_geopackage(connection: &Connection) -> Result<bool, FileError> {
+    Ok(connection.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' \
+         AND name = 'gpkg_geometry_columns')",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// Returns the first feature table of a GeoPackage in alphabetical order.
+pub(crate) fn feature_table(connection: &Connection) -> Result<String, FileError> {
+    Ok(connection.query_row(
+        "SELECT c.table_name FROM gpkg_contents c \
+         JOIN gpkg_geometry_columns g ON c.table_name = g.table_name \
+         WHERE c.data_type = 'features' ORDER BY c.table_name LIMIT 1",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// Returns the names of the geometry columns of all feature tables of a GeoPackage.
+pub(crate) fn geometry_columns(connection: &Connection) -> Result<Vec<String>, FileError> {
+    let mut statement = connection.prepare("SELECT column_name FROM gpkg_geometry_columns")?;
+    let columns = statement
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(columns)
+}
+
+/// Renders a GeoPackage geometry blob, i.e. a small header followed by WKB, as WKT.
+pub(crate) fn read_gpkg_geometry(blob: &[u8]) -> Result<String, FileError> {
+    if blob.len() < 8 || &blob[..2] != b"GP" {
+        return Err(FileError::InvalidGeometry(
+            "missing GeoPackage geometry header".to_string(),
+        ));
+    }
+    let envelope = match (blob[3] >> 1) & 0b111 {
+        0 => 0,
+        1 => 32,
+        2 | 3 => 48,
+        4 => 64,
+        indicator => {
+            return Err(FileError::InvalidGeometry(format!(
+                "invalid envelope indicator {}",
+                indicator
+            )))
+        }
+    };
+    Ok(parse_wkb(&blob[8 + envelope.min(blob.len() - 8)..])?.wkt())
+}
+
+/// Parses a geometry in well-known binary representation.
+/// Both ISO (e.g. 1001 for a point with z) and extended (EWKB) dimension flags are supported.
+fn parse_wkb(data: &[u8]) -> Result<Geometry, FileError> {
+    let mut reader = WkbReader { data, position: 0 };
+    reader.geometry()
+}
+
+struct WkbReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl WkbReader<'_> {
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], FileError> {
+        let bytes = self
+            .data
+            .get(self.position..self.position + N)
+            .ok_or_else(|| invalid_wkb("unexpected end of geometry"))?;
+        self.position += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn geometry(&mut self) -> Result<Geometry, FileError> {
+        let little_endian = self.bytes::<1>()?[0] == 1;
+        let u32 = |bytes| {
+            if little_endian {
+                u32::from_le_bytes(bytes)
+            } else {
+                u32::from_be_bytes(bytes)
+            }
+        };
+        let raw_type = u32(self.bytes()?);
+        let base_type = raw_type & 0x0fff_ffff;
+        let mut dimensions = match base_type / 1000 {
+            1 | 2 => 3,
+            3 => 4,
+            _ => 2,
+        };
+        if raw_type & 0x8000_0000 != 0 {
+            dimensions += 1;
+        }
+        if raw_type & 0x4000_0000 != 0 {
+            dimensions += 1;
+        }
+        if raw_type & 0x2000_0000 != 0 {
+            self.bytes::<4>()?;
+        }
+        let mut reader = Coordinates {
+            wkb: self,
+            little_endian,
+            dimensions,
+        };
+        Ok(match base_type % 1000 {
+            1 => {
+                let position = reader.position()?;
+                Geometry::Point((!position.0.is_nan() || !position.1.is_nan()).then_some(position))
+            }
+            2 => Geometry::LineString(reader.positions()?),
+            3 => Geometry::Polygon(reader.rings()?),
+            kind @ 4..=7 => {
+                let count = reader.count()?;
+                let mut geometries = Vec::with_capacity(count.min(1024));
+                for _ in 0..count {
+                    geometries.push(reader.wkb.geometry()?);
+                }
+                match kind {
+                    4 => Geometry::MultiPoint(
+                        geometries
+                            .into_iter()
+                            .filter_map(|geometry| match geometry {
+                                Geometry::Point(position) => position,
+                                _ => None,
+                            })
+                            .collect(),
+                    ),
+                    5 => Geometry::MultiLineString(
+                        geometries
+                            .into_iter()
+                            .filter_map(|geometry| match geometry {
+                                Geometry::LineString(line) => Some(line),
+                                _ => None,
+                            })
+                            .collect(),
+                    ),
+                    6 => Geometry::MultiPolygon(
+                        geometries
+                            .into_iter()
+                            .filter_map(|geometry| match geometry {
+                                Geometry::Polygon(rings) => Some(rings),
+                                _ => None,
+                            })
+                            .collect(),
+                    ),
+                    _ => Geometry::Collection(geometries),
+                }
+            }
+            _ => {
+                return Err(invalid_wkb(&format!(
+                    "unsupported geometry type {}",
+                    raw_type
+                )))
+            }
+        })
+    }
+}
+
+/// Reads the coordinates of a single geometry with a fixed byte order and dimension.
+struct Coordinates<'a, 'b> {
+    wkb: &'a mut WkbReader<'b>,
+    little_endian: bool,
+    dimensions: usize,
+}
+
+impl Coordinates<'_, '_> {
+    fn count(&mut self) -> Result<usize, FileError> {
+        let bytes = self.wkb.bytes()?;
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        } as usize)
+    }
+
+    fn float(&mut self) -> Result<f64, FileError> {
+        let bytes = self.wkb.bytes()?;
+        Ok(if self.little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    fn position(&mut self) -> Result<Position, FileError> {
+        let position = (self.float()?, self.float()?);
+        for _ in 2..self.dimensions {
+            self.float()?;
+        }
+        Ok(position)
+    }
+
+    fn positions(&mut self) -> Result<Vec<Position>, FileError> {
+        let count = self.count()?;
+        (0..count).map(|_| self.position()).collect()
+    }
+
+    fn rings(&mut self) -> Result<Vec<Vec<Position>>, FileError> {
+        let count = self.count()?;
+        (0..count).map(|_| self.positions()).collect()
+    }
+}
+
+fn invalid_wkb(message: &str) -> FileError {
+    FileError::InvalidGeometry(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileReader;
+
+    #[test]
+    fn test_read_gpkg_table() {
+        let mut reader = FileReader::new("tests/cities.gpkg", None)
+            .expect("Failed to create FileReader")
+            .null_output("NA");
+        assert_eq!(
+            reader.headers().unwrap(),
+            vec!["fid", "geom", "name", "population", "area"]
+        );
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(
+            records,
+            vec![
+                vec!["1", "POINT (13.405 52.52)", "Berlin", "3850809", "891.7"],
+                vec!["2", "POINT (11.575 48.1375)", "München", "1512491", "NA"],
+                vec!["3", "NA", "Nowhere", "NA", "NA"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_gpkg_geometry() {
+        assert!(read_gpkg_geometry(b"XX").is_err());
+    }
+
+    #[test]
+    fn test_parse_wkb() {
+        let mut line = vec![1, 2, 0, 0, 0, 2, 0, 0, 0];
+        for value in [0.0f64, 0.0, 1.0, 1.5] {
+            line.extend(value.to_le_bytes());
+        }
+        assert_eq!(parse_wkb(&line).unwrap().wkt(), "LINESTRING (0 0, 1 1.5)");
+        let mut point_z = vec![0, 0, 0, 0x03, 0xe9];
+        for value in [3.0f64, 4.0, 5.0] {
+            point_z.extend(value.to_be_bytes());
+        }
+        assert_eq!(parse_wkb(&point_z).unwrap().wkt(), "POINT (3 4)");
+        assert!(parse_wkb(&line[..12]).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{FileError, FileReader};
+
+/// The hash of a record together with the values of its key columns, see
+/// [`FileReader::record_hashes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordHash {
+    /// The values of the key columns, which identify the record across runs.
+    pub key: Vec<String>,
+    /// The hash of all values of the record.
+    pub hash: u64,
+}
+
+impl FileReader {
+    /// Returns an iterator over the hashes of the records of the file together with the values
+    /// of the given key columns. The hashes are computed with 64-bit FNV-1a and are stable
+    /// across runs, platforms and versions, so they can be stored to detect changed records
+    /// in later runs without keeping copies of the records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let hashes: Vec<_> = reader.record_hashes(&["Name"]).unwrap().collect();
+    /// assert_eq!(hashes[0].key, vec!["John"]);
+    /// assert_ne!(hashes[0].hash, hashes[1].hash);
+    /// ```
+    pub fn record_hashes(
+        &mut self,
+        key_columns: &[&str],
+    ) -> Result<impl Iterator<Item = RecordHash> + '_, FileError> {
+        let headers = self.headers()?;
+        let key_indices = key_columns
+            .iter()
+            .map(|column| {
+                headers
+                    .iter()
+                    .position(|header| header == column)
+                    .ok_or_else(|| FileError::UnknownColumn(column.to_string()))
+            })
+            .collect::<Result<Vec<usize>, FileError>>()?;
+        Ok(self.records()?.map(move |record| RecordHash {
+            key: key_indices
+                .iter()
+                .map(|&index| record.get(index).cloned().unwrap_or_default())
+                .collect(),
+            hash: hash_record(&record),
+        }))
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hashes the values of a record with 64-bit FNV-1a. Each value is prefixed with its length,
+/// so that records with the same concatenated values hash differently.
+fn hash_record(record: &[String]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    for value in record {
+        let len = value.len() as u64;
+        for byte in len.to_le_bytes().iter().chain(value.as_bytes()) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_record() {
+        let record = |values: &[&str]| -> Vec<String> {
+            values.iter().map(|value| value.to_string()).collect()
+        };
+        assert_eq!(hash_record(&[]), FNV_OFFSET_BASIS);
+        assert_eq!(hash_record(&record(&["John", "30"])), 0xf0f6_47c6_3acd_90b1);
+        assert_eq!(
+            hash_record(&record(&["John", "30"])),
+            hash_record(&record(&["John", "30"]))
+        );
+        assert_ne!(
+            hash_record(&record(&["ab", "c"])),
+            hash_record(&record(&["a", "bc"]))
+        );
+    }
+
+    #[test]
+    fn test_unknown_key_column() {
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        assert_eq!(
+            reader.record_hashes(&["Missing"]).err(),
+            Some(FileError::UnknownColumn("Missing".to_string()))
+        );
+    }
+}
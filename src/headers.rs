@@ -0,0 +1,261 @@
+use crate::{FileError, FileReader};
+use std::collections::HashSet;
+
+/// A transformation applied to headers, see [`FileReader::rename_headers`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderTransform {
+    /// Converts headers to lowercase, e.g. `Sample Name` to `sample name`.
+    Lowercase,
+    /// Converts headers to lowercase words separated by underscores, e.g. `Sample Name`,
+    /// `SampleName` and `sample-name` to `sample_name`.
+    SnakeCase,
+}
+
+impl HeaderTransform {
+    fn apply(&self, header: &str) -> String {
+        match self {
+            HeaderTransform::Lowercase => header.to_lowercase(),
+            HeaderTransform::SnakeCase => snake_case(header),
+        }
+    }
+}
+
+/// How headers are renamed, see [`FileReader::header_map`] and
+/// [`FileReader::rename_headers`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HeaderRenaming {
+    map: Vec<(String, String)>,
+    transform: Option<HeaderTransform>,
+}
+
+impl HeaderRenaming {
+    pub(crate) fn apply(&self, headers: Vec<String>) -> Vec<String> {
+        headers
+            .into_iter()
+            .map(
+                |header| match self.map.iter().find(|(original, _)| *original == header) {
+                    Some((_, renamed)) => renamed.clone(),
+                    None => match &self.transform {
+                        Some(transform) => transform.apply(&header),
+                        None => header,
+                    },
+                },
+            )
+            .collect()
+    }
+}
+
+impl FileReader {
+    /// Renames the headers of the file. Each pair maps an original header to its new name.
+    /// The new names are used for selecting columns and everywhere else headers are used.
+    /// Headers that are not mapped are transformed by [`FileReader::rename_headers`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(','))
+    ///     .expect("Failed to create FileReader")
+    ///     .header_map(&[("Name", "name"), ("Country", "country")]);
+    /// assert_eq!(reader.headers().unwrap(), vec!["name", "Age", "country"]);
+    /// ```
+    pub fn header_map<K: AsRef<str>, V: AsRef<str>>(mut self, map: &[(K, V)]) -> FileReader {
+        self.header_renaming.map = map
+            .iter()
+            .map(|(original, renamed)| {
+                (original.as_ref().to_string(), renamed.as_ref().to_string())
+            })
+            .collect();
+        self
+    }
+
+    /// Sets a transformation that renames all headers not renamed by
+    /// [`FileReader::header_map`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{FileReader, HeaderTransform};
+    ///
+    /// let mut reader = FileReader::new("tests/rename_test.csv", Some(','))
+    ///     .expect("Failed to create FileReader")
+    ///     .rename_headers(HeaderTransform::SnakeCase);
+    /// assert_eq!(
+    ///     reader.headers().unwrap(),
+    ///     vec!["sample_name", "read_count", "tissue_type", "http_status"]
+    /// );
+    /// ```
+    pub fn rename_headers(mut self, transform: HeaderTransform) -> FileReader {
+        self.header_renaming.transform = Some(transform);
+        self
+    }
+
+    /// Sets whether duplicate and empty headers are renamed instead of rejected with
+    /// [`FileError::InvalidHeaders`]. Empty headers are named after their position like
+    /// `column_3` and repeated headers get a numeric suffix like `name_2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/duplicate_headers.csv", Some(','))
+    ///     .expect("Failed to create FileReader")
+    ///     .dedupe_headers(true);
+    /// assert_eq!(reader.headers().unwrap(), vec!["name", "value", "name_2", "column_4"]);
+    /// ```
+    pub fn dedupe_headers(mut self, dedupe_headers: bool) -> FileReader {
+        self.dedupe_headers = dedupe_headers;
+        self
+    }
+}
+
+/// Returns an error describing the first duplicate or empty header. The first header may be
+/// empty, as the row label columns of matrices and pandas exports have none.
+pub(crate) fn validate_headers(headers: &[String]) -> Result<(), FileError> {
+    let mut seen = HashSet::new();
+    for (index, header) in headers.iter().enumerate() {
+        if index > 0 && header.trim().is_empty() {
+            return Err(FileError::InvalidHeaders(format!(
+                "empty header in column {}",
+                index + 1
+            )));
+        }
+        if !seen.insert(header) {
+            return Err(FileError::InvalidHeaders(format!(
+                "duplicate header '{}'",
+                header
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Names empty headers after their position and appends a suffix to repeated headers, so that
+/// all headers are unique and non-empty.
+pub(crate) fn dedupe_headers(headers: Vec<String>) -> Vec<String> {
+    let headers: Vec<String> = headers
+        .into_iter()
+        .enumerate()
+        .map(|(index, header)| {
+            if header.trim().is_empty() {
+                format!("column_{}", index + 1)
+            } else {
+                header
+            }
+        })
+        .collect();
+    let original: HashSet<String> = headers.iter().cloned().collect();
+    let mut used = HashSet::new();
+    headers
+        .into_iter()
+        .map(|header| {
+            let mut unique = header.clone();
+            let mut suffix = 2;
+            while used.contains(&unique) || (unique != header && original.contains(&unique)) {
+                unique = format!("{}_{}", header, suffix);
+                suffix += 1;
+            }
+            used.insert(unique.clone());
+            unique
+        })
+        .collect()
+}
+
+/// Converts a header to lowercase words separated by underscores. Words are separated by
+/// characters other than letters and digits and by case changes, keeping acronyms together.
+fn snake_case(header: &str) -> String {
+    let chars: Vec<char> = header.chars().collect();
+    let mut snake = String::new();
+    let mut separate = false;
+    for (index, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            separate = !snake.is_empty();
+            continue;
+        }
+        let previous = index.checked_sub(1).map(|index| chars[index]);
+        let next = chars.get(index + 1);
+        let word_start = c.is_uppercase()
+            && previous.is_some_and(|previous| {
+                previous.is_lowercase()
+                    || previous.is_ascii_digit()
+                    || (previous.is_uppercase() && next.is_some_and(|next| next.is_lowercase()))
+            });
+        if (separate || word_start) && !snake.is_empty() {
+            snake.push('_');
+        }
+        separate = false;
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_validate_headers() {
+        assert!(validate_headers(&strings(&["a", "b"])).is_ok());
+        assert!(validate_headers(&strings(&["", "a", "b"])).is_ok());
+        assert_eq!(
+            validate_headers(&strings(&["a", "b", "a"])),
+            Err(FileError::InvalidHeaders(
+                "duplicate header 'a'".to_string()
+            ))
+        );
+        assert_eq!(
+            validate_headers(&strings(&["a", " "])),
+            Err(FileError::InvalidHeaders(
+                "empty header in column 2".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_dedupe_headers() {
+        assert_eq!(
+            dedupe_headers(strings(&["a", "a", "a_2", "", "a"])),
+            vec!["a", "a_3", "a_2", "column_4", "a_4"]
+        );
+    }
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("Sample Name"), "sample_name");
+        assert_eq!(snake_case("sampleName"), "sample_name");
+        assert_eq!(snake_case("  tissue--type "), "tissue_type");
+        assert_eq!(snake_case("HTTPStatus2xx"), "http_status2xx");
+        assert_eq!(snake_case("already_snake"), "already_snake");
+    }
+
+    #[test]
+    fn test_select_renamed_columns() {
+        let mut reader = FileReader::new("tests/rename_test.csv", Some(','))
+            .expect("Failed to create FileReader")
+            .header_map(&[("ReadCount", "reads")])
+            .rename_headers(HeaderTransform::Lowercase);
+        let missing = reader.select_columns(&["reads", "sample name"]).unwrap();
+        assert!(missing.is_empty());
+        assert_eq!(reader.headers().unwrap(), vec!["reads", "sample name"]);
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records, vec![vec!["10", "A"]]);
+    }
+
+    #[test]
+    fn test_invalid_csv_headers() {
+        let mut reader = FileReader::new("tests/duplicate_headers.csv", Some(','))
+            .expect("Failed to create FileReader");
+        assert_eq!(
+            reader.headers(),
+            Err(FileError::InvalidHeaders(
+                "duplicate header 'name'".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{csv_reader_builder, finish_record, FileError, FileFormat, FileReader};
+use serde_json::Value;
+use std::io::{BufRead, Seek, SeekFrom};
+use std::ops::Range;
+
+/// An index of the records of a file for random access by record number.
+/// The byte offset of every record of CSV and newline-delimited JSON files is stored, so
+/// records anywhere in the file are read without reading the file from its start. Records of
+/// other file formats and of newline-delimited JSON files with exploded arrays are skipped up
+/// to the requested ones instead.
+/// Record numbers count the records returned by [`FileReader::records`] from 0.
+///
+/// # Examples
+///
+/// ```
+/// use readervzrd::{FileReader, RecordIndex};
+///
+/// let reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+/// let mut index = RecordIndex::new(reader).expect("Failed to build index");
+/// assert_eq!(index.len(), 3);
+/// assert_eq!(index.get(2).unwrap(), Some(vec!["Bob".to_string(), "40".to_string(), "Canada".to_string()]));
+/// assert_eq!(index.range(0..2).unwrap().len(), 2);
+/// ```
+pub struct RecordIndex {
+    reader: FileReader,
+    offsets: Option<Vec<u64>>,
+    len: usize,
+    /// The number of headers records of flexible CSV files are fitted to.
+    width: Option<usize>,
+}
+
+impl RecordIndex {
+    /// Builds the index by reading the file once.
+    pub fn new(mut reader: FileReader) -> Result<RecordIndex, FileError> {
+        let offsets = match reader.file_format {
+            FileFormat::Csv(delimiter) => Some(reader.csv_offsets(delimiter)?),
+            FileFormat::Ndjson if !reader.json_rendering.explodes() => {
+                Some(reader.ndjson_offsets()?)
+            }
+            _ => None,
+        };
+        let len = match &offsets {
+            Some(offsets) => offsets.len(),
+            None => reader.records()?.count(),
+        };
+        let width = reader.flexible_width()?;
+        reader.file.seek(SeekFrom::Start(0))?;
+        Ok(RecordIndex {
+            reader,
+            offsets,
+            len,
+            width,
+        })
+    }
+
+    /// Returns the number of records in the file.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the file has no records.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the record with the given number, or `None` if there are fewer records.
+    pub fn get(&mut self, row: usize) -> Result<Option<Vec<String>>, FileError> {
+        Ok(self.range(row..row.saturating_add(1))?.pop())
+    }
+
+    /// Returns the records with numbers in the given range. The range is truncated to the
+    /// records of the file.
+    pub fn range(&mut self, rows: Range<usize>) -> Result<Vec<Vec<String>>, FileError> {
+        let rows = rows.start.min(self.len)..rows.end.min(self.len);
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+        let records = match (&self.offsets, self.reader.file_format) {
+            (Some(offsets), FileFormat::Csv(delimiter)) => {
+                self.reader.read_csv_at(delimiter, &offsets[rows])?
+            }
+            (Some(offsets), _) => self.reader.read_ndjson_at(&offsets[rows])?,
+            (None, _) => {
+                return self
+                    .reader
+                    .records_limit(rows.start, rows.len())
+                    .map(Iterator::collect)
+            }
+        };
+        self.reader.file.seek(SeekFrom::Start(0))?;
+        Ok(records
+            .into_iter()
+            .map(|record| {
+                finish_record(
+                    record,
+                    self.width,
+                    self.reader.columns.as_deref(),
+                    &self.reader.normalization,
+                )
+                .0
+            })
+            .collect())
+    }
+
+    /// Returns the reader the index has been built for.
+    pub fn into_reader(self) -> FileReader {
+        self.reader
+    }
+}
+
+impl FileReader {
+    /// Returns the byte offsets of the well-formed records of a CSV file.
+    fn csv_offsets(&mut self, delimiter: char) -> Result<Vec<u64>, FileError> {
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut reader =
+            csv_reader_builder(delimiter, &self.csv_dialect).from_reader(&mut self.file);
+        let mut record = csv::StringRecord::new();
+        let mut offsets = Vec::new();
+        loop {
+            match reader.read_record(&mut record) {
+                Ok(true) => offsets.extend(record.position().map(|position| position.byte())),
+                Ok(false) => break,
+                Err(_) => {}
+            }
+        }
+        Ok(offsets)
+    }
+
+    /// Returns the byte offsets of the lines of a newline-delimited JSON file that contain a
+    /// valid JSON value.
+    fn ndjson_offsets(&mut self) -> Result<Vec<u64>, FileError> {
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut offsets = Vec::new();
+        let mut offset = 0;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let len = self.file.read_until(b'\n', &mut line)?;
+            if len == 0 {
+                break;
+            }
+            if serde_json::from_slice::<Value>(&line).is_ok() {
+                offsets.push(offset);
+            }
+            offset += len as u64;
+        }
+        Ok(offsets)
+    }
+
+    /// Reads the CSV records starting at the given consecutive offsets of well-formed records.
+    fn read_csv_at(
+        &mut self,
+        delimiter: char,
+        offsets: &[u64],
+    ) -> Result<Vec<Vec<String>>, FileError> {
+        let start = offsets[0];
+        self.file.seek(SeekFrom::Start(start))?;
+        let mut reader = csv_reader_builder(delimiter, &self.csv_dialect)
+            .has_headers(false)
+            .from_reader(&mut self.file);
+        let mut record = csv::StringRecord::new();
+        let mut records = Vec::with_capacity(offsets.len());
+        while records.len() < offsets.len() {
+            match reader.read_record(&mut record) {
+                Ok(true) => {
+                    let offset = start + record.position().map_or(0, |position| position.byte());
+                    if offset == offsets[records.len()] {
+                        records.push(record.iter().map(String::from).collect());
+                    }
+                }
+                Ok(false) => break,
+                Err(_) => {}
+            }
+        }
+        Ok(records)
+    }
+
+    /// Reads the newline-delimited JSON records at the given offsets.
+    fn read_ndjson_at(&mut self, offsets: &[u64]) -> Result<Vec<Vec<String>>, FileError> {
+        self.file.seek(SeekFrom::Start(offsets[0]))?;
+        let mut records = Vec::with_capacity(offsets.len());
+        let mut offset = offsets[0];
+        let mut line = Vec::new();
+        while records.len() < offsets.len() {
+            line.clear();
+            let len = self.file.read_until(b'\n', &mut line)?;
+            if len == 0 {
+                break;
+            }
+            if offset == offsets[records.len()] {
+                let value: Value = serde_json::from_slice(&line)?;
+                records.extend(
+                    self.json_rendering
+                        .render(value, self.normalization.null_output()),
+                );
+            }
+            offset += len as u64;
+        }
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_index_skips_malformed_records() {
+        let reader = FileReader::new("tests/malformed_test.csv", Some(','))
+            .expect("Failed to create FileReader");
+        let mut index = RecordIndex::new(reader).unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.get(1).unwrap().unwrap(), vec!["Bob", "40", "Canada"]);
+        assert_eq!(index.get(2).unwrap(), None);
+        assert_eq!(index.range(0..5).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_flexible_csv_index() {
+        let reader = FileReader::new("tests/dialect_test.tsv", Some('\t'))
+            .expect("Failed to create FileReader")
+            .comment(Some('#'))
+            .quote('\'')
+            .escape(Some('\\'))
+            .trim(true)
+            .flexible(true);
+        let mut index = RecordIndex::new(reader).unwrap();
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.get(1).unwrap().unwrap(), vec!["2", "c", ""]);
+        assert_eq!(index.get(2).unwrap().unwrap(), vec!["3", "d", "e"]);
+    }
+
+    #[test]
+    fn test_ndjson_index() {
+        let mut reader =
+            FileReader::new("tests/test.ndjson", None).expect("Failed to create FileReader");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        let mut index = RecordIndex::new(reader).unwrap();
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.range(1..3).unwrap(), records[1..3]);
+        assert_eq!(index.get(0).unwrap().unwrap(), records[0]);
+    }
+
+    #[test]
+    fn test_index_without_offsets() {
+        let mut reader =
+            FileReader::new("tests/test.json", None).expect("Failed to create FileReader");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        let mut index = RecordIndex::new(reader).unwrap();
+        assert_eq!(index.len(), records.len());
+        assert_eq!(index.get(2).unwrap().unwrap(), records[2]);
+    }
+}
@@ -0,0 +1,295 @@
+use crate::FileError;
+use serde_json::Value;
+use std::io::{self, BufRead};
+
+/// An iterator over the items of a JSON stream, i.e. the elements of top-level JSON arrays
+/// and top-level objects of newline-delimited JSON files.
+/// Items are parsed one at a time, so only a single item is buffered in memory regardless
+/// of the size of the document or the length of its lines.
+pub(crate) struct JsonItems<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    in_array: bool,
+    done: bool,
+    current_line: u64,
+    line: u64,
+}
+
+impl<R: BufRead> JsonItems<R> {
+    pub(crate) fn new(reader: R) -> JsonItems<R> {
+        JsonItems {
+            reader,
+            buffer: Vec::new(),
+            in_array: false,
+            done: false,
+            current_line: 1,
+            line: 0,
+        }
+    }
+
+    /// Returns the line number at which the last returned item starts.
+    pub(crate) fn line(&self) -> u64 {
+        self.line
+    }
+
+    /// Skips whitespace and returns the next byte without consuming it.
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        loop {
+            let buf = self.reader.fill_buf()?;
+            if buf.is_empty() {
+                return Ok(None);
+            }
+            match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+                Some(index) => {
+                    let byte = buf[index];
+                    self.current_line += count_lines(&buf[..index]);
+                    self.reader.consume(index);
+                    return Ok(Some(byte));
+                }
+                None => {
+                    let len = buf.len();
+                    self.current_line += count_lines(buf);
+                    self.reader.consume(len);
+                }
+            }
+        }
+    }
+
+    /// Reads the next complete JSON value into the buffer.
+    fn read_value(&mut self) -> io::Result<()> {
+        self.buffer.clear();
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        loop {
+            let buf = self.reader.fill_buf()?;
+            if buf.is_empty() {
+                if depth == 0 && !in_string && !self.buffer.is_empty() {
+                    return Ok(());
+                }
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+            }
+            let mut end = None;
+            for (index, &byte) in buf.iter().enumerate() {
+                if in_string {
+                    match byte {
+                        _ if escaped => escaped = false,
+                        b'\\' => escaped = true,
+                        b'"' => {
+                            in_string = false;
+                            if depth == 0 {
+                                end = Some(index + 1);
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+                match byte {
+                    b'"' => in_string = true,
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' if depth > 0 => {
+                        depth -= 1;
+                        if depth == 0 {
+                            end = Some(index + 1);
+                            break;
+                        }
+                    }
+                    b',' | b'}' | b']' if depth == 0 => {
+                        end = Some(index);
+                        break;
+                    }
+                    _ if byte.is_ascii_whitespace() && depth == 0 => {
+                        end = Some(index);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            match end {
+                Some(end) => {
+                    self.buffer.extend_from_slice(&buf[..end]);
+                    self.current_line += count_lines(&buf[..end]);
+                    self.reader.consume(end);
+                    return Ok(());
+                }
+                None => {
+                    let len = buf.len();
+                    self.buffer.extend_from_slice(buf);
+                    self.current_line += count_lines(buf);
+                    self.reader.consume(len);
+                }
+            }
+        }
+    }
+
+    fn next_item(&mut self) -> Result<Option<Value>, FileError> {
+        loop {
+            let Some(byte) = self.peek()? else {
+                if self.in_array {
+                    return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+                }
+                return Ok(None);
+            };
+            match byte {
+                b'[' if !self.in_array => {
+                    self.reader.consume(1);
+                    self.in_array = true;
+                }
+                b']' if self.in_array => {
+                    self.reader.consume(1);
+                    self.in_array = false;
+                }
+                b',' if self.in_array => self.reader.consume(1),
+                _ => {
+                    self.line = self.current_line;
+                    self.read_value()?;
+                    let value = serde_json::from_slice(&self.buffer)?;
+                    return match value {
+                        Value::Object(_) => Ok(Some(value)),
+                        _ if self.in_array => Ok(Some(value)),
+                        _ => Err(FileError::InvalidJsonStructure),
+                    };
+                }
+            }
+        }
+    }
+}
+
+fn count_lines(bytes: &[u8]) -> u64 {
+    bytes.iter().filter(|&&byte| byte == b'\n').count() as u64
+}
+
+impl<R: BufRead> Iterator for JsonItems<R> {
+    type Item = Result<Value, FileError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.next_item() {
+            Ok(Some(value)) => Some(Ok(value)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                // Structural errors leave the stream in an unknown state, so stop reading.
+                if !matches!(err, FileError::JsonError(_)) {
+                    self.done = true;
+                }
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufReader, Read};
+
+    fn parse(input: &str) -> Vec<Result<Value, FileError>> {
+        JsonItems::new(input.as_bytes()).collect()
+    }
+
+    #[test]
+    fn test_json_array_items() {
+        let items = parse(r#" [ {"a": 1, "c": [{}]}, {"b": "x,]}"}, [1, 2], 3, "s\"]" ] "#);
+        assert_eq!(
+            items.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            vec![
+                serde_json::json!({"a": 1, "c": [{}]}),
+                serde_json::json!({"b": "x,]}"}),
+                serde_json::json!([1, 2]),
+                serde_json::json!(3),
+                serde_json::json!("s\"]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ndjson_items() {
+        let items = parse("{\"a\": 1}\n{\"a\": 2}\n\n");
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].as_ref().unwrap(), &serde_json::json!({"a": 2}));
+    }
+
+    #[test]
+    fn test_json_item_lines() {
+        let mut items =
+            JsonItems::new("[\n  {\"a\": 1},\n  {\n    \"a\": 2\n  },\n  {}\n]".as_bytes());
+        let mut lines = Vec::new();
+        while items.next().is_some() {
+            lines.push(items.line());
+        }
+        assert_eq!(lines, vec![2, 3, 6]);
+    }
+
+    #[test]
+    fn test_invalid_json_items() {
+        let items = parse(r#"[{"a": 1}, {"a": }, {"a": 3}]"#);
+        assert_eq!(items.len(), 3);
+        assert!(matches!(items[1], Err(FileError::JsonError(_))));
+        assert_eq!(items[2].as_ref().unwrap(), &serde_json::json!({"a": 3}));
+
+        let items = parse(r#"[{"a": 1}, {"a": 2"#);
+        assert_eq!(items.len(), 2);
+        assert!(matches!(items[1], Err(FileError::IoError(_))));
+
+        let items = parse("42");
+        assert_eq!(
+            items[0].as_ref().err(),
+            Some(&FileError::InvalidJsonStructure)
+        );
+    }
+
+    /// Generates a minified single-line JSON array of the given number of objects on the fly.
+    struct GeneratedJsonArray {
+        record: Vec<u8>,
+        records: usize,
+        written: usize,
+        offset: usize,
+    }
+
+    impl Read for GeneratedJsonArray {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let chunk: &[u8] = match self.written {
+                0 => b"[",
+                n if n <= self.records => &self.record[usize::from(n == 1)..],
+                n if n == self.records + 1 => b"]",
+                _ => return Ok(0),
+            };
+            let len = buf.len().min(chunk.len() - self.offset);
+            buf[..len].copy_from_slice(&chunk[self.offset..self.offset + len]);
+            self.offset += len;
+            if self.offset == chunk.len() {
+                self.offset = 0;
+                self.written += 1;
+            }
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_long_single_line_json() {
+        // 300 MB of JSON without a single line break.
+        let records = 300_000;
+        let record = format!(r#",{{"id":1,"name":"{}"}}"#, "x".repeat(983)).into_bytes();
+        let input = GeneratedJsonArray {
+            record,
+            records,
+            written: 0,
+            offset: 0,
+        };
+        let mut items = JsonItems::new(BufReader::new(input));
+        let mut count = 0;
+        for item in items.by_ref() {
+            item.unwrap();
+            count += 1;
+        }
+        assert_eq!(count, records);
+        assert!(items.buffer.capacity() < 4096);
+    }
+}
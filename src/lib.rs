@@ -1,31 +1,180 @@
-use serde_json::{Deserializer, Value};
+use compression::DecompressedSource;
+use dialect::{csv_reader_builder, CsvDialect};
+use encoding::Transcoder;
+use headers::HeaderRenaming;
+use json::JsonItems;
+use normalize::Normalization;
+use render::JsonRendering;
+use serde_json::Value;
 use std::fs::File;
-use std::io::{self, BufReader, Seek, SeekFrom};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::sync::atomic::Ordering;
+use stream::StreamSource;
 use thiserror::Error;
 
-enum FileFormat {
+#[cfg(feature = "bench")]
+mod bench;
+mod checkpoint;
+mod compare;
+mod compression;
+mod deserialize;
+mod dialect;
+mod encoding;
+mod filter;
+mod geojson;
+#[cfg(feature = "gpkg")]
+mod gpkg;
+mod hash;
+mod headers;
+mod index;
+mod json;
+mod log;
+mod materialize;
+mod matrix;
+mod multi;
+mod normalize;
+#[cfg(feature = "pdf")]
+mod pdf;
+mod profile;
+mod progress;
+#[cfg(feature = "arrow")]
+mod record_batch;
+#[cfg(feature = "remote")]
+mod remote;
+mod render;
+mod sample;
+mod schema;
+#[cfg(feature = "sdmx")]
+mod sdmx;
+#[cfg(feature = "shapefile")]
+mod shapefile;
+mod sort;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod stats;
+mod stream;
+mod syslog;
+mod tree;
+mod types;
+mod vobject;
+#[cfg(any(feature = "shapefile", feature = "gpkg"))]
+mod wkt;
+mod writer;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+#[cfg(feature = "bench")]
+pub use bench::Timings;
+pub use checkpoint::Checkpoint;
+pub use compare::{FileDiff, MalformedRow, RowDiff};
+pub use compression::{Decompressor, Gzip};
+pub use filter::Filter;
+pub use hash::RecordHash;
+pub use headers::HeaderTransform;
+pub use index::RecordIndex;
+pub use log::LogFormat;
+pub use materialize::MaterializedTable;
+pub use matrix::Matrix;
+pub use multi::MultiFileReader;
+pub use profile::Profile;
+pub use progress::Progress;
+pub use render::ArrayRendering;
+pub use schema::{RetypedColumn, Schema, SchemaChanges, SchemaColumn};
+pub use sort::Order;
+pub use stats::ColumnStats;
+pub use tree::{Table, TreeReader, TreeRecord};
+pub use types::ColumnType;
+pub use writer::FileWriter;
+
+/// The format of a file, which is derived from its extension unless it is given explicitly
+/// with [`FileReader::from_reader`] or [`FileReader::from_stdin`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum FileFormat {
+    /// CSV with the given delimiter.
     Csv(char),
+    /// A JSON array of objects.
     Json,
+    /// Newline-delimited JSON with one object per line.
+    Ndjson,
+    /// A GeoJSON feature collection.
+    GeoJson,
+    /// iCalendar events.
+    Ics,
+    /// vCard contacts.
+    Vcard,
+    /// Server logs, see [`LogFormat`].
+    Log,
+    /// Syslog messages.
+    Syslog,
+    /// Journal exports written by `journalctl -o export`.
+    Journal,
+    /// Ruled tables of PDF reports.
+    #[cfg(feature = "pdf")]
+    Pdf,
+    /// SDMX-ML data messages.
+    #[cfg(feature = "sdmx")]
+    Sdmx,
+    /// SQLite databases, including GeoPackages.
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+    /// YAML sequences of mappings.
+    #[cfg(feature = "yaml")]
+    Yaml,
 }
 
 impl FileFormat {
-    pub fn from_file(file_path: &str, delimiter: Option<char>) -> Result<FileFormat, FileError> {
+    pub(crate) fn from_file(
+        file_path: &str,
+        delimiter: Option<char>,
+    ) -> Result<FileFormat, FileError> {
         match (
             std::path::Path::new(file_path)
                 .extension()
-                .unwrap()
-                .to_str(),
+                .and_then(|extension| extension.to_str()),
             delimiter,
         ) {
             (Some("csv" | "tsv"), Some(d)) => Ok(FileFormat::Csv(d)),
             (Some("json"), _) => Ok(FileFormat::Json),
+            (Some("ndjson" | "jsonl"), _) => Ok(FileFormat::Ndjson),
+            (Some("geojson"), _) => Ok(FileFormat::GeoJson),
+            (Some("ics" | "ical"), _) => Ok(FileFormat::Ics),
+            (Some("vcf" | "vcard"), _) => Ok(FileFormat::Vcard),
+            (Some("log"), _) => Ok(FileFormat::Log),
+            (Some("syslog"), _) => Ok(FileFormat::Syslog),
+            (Some("export"), _) => Ok(FileFormat::Journal),
+            #[cfg(feature = "pdf")]
+            (Some("pdf"), _) => Ok(FileFormat::Pdf),
+            #[cfg(feature = "sdmx")]
+            (Some("xml" | "sdmx"), _) => Ok(FileFormat::Sdmx),
+            #[cfg(feature = "sqlite")]
+            (Some("db" | "sqlite" | "sqlite3"), _) => Ok(FileFormat::Sqlite),
+            #[cfg(feature = "gpkg")]
+            (Some("gpkg"), _) => Ok(FileFormat::Sqlite),
+            #[cfg(feature = "yaml")]
+            (Some("yaml" | "yml"), _) => Ok(FileFormat::Yaml),
             _ => Err(FileError::UnknownFileFormat),
         }
     }
 }
 
 /// A struct that reads records from a file.
-/// The file can be in CSV or JSON format.
+/// The file can be in CSV, JSON, newline-delimited JSON or GeoJSON format. GeoJSON features
+/// are read like JSON objects of their properties with an additional `geometry` column
+/// containing the geometry as WKT. iCalendar and vCard files are read with one record per
+/// event or contact and a fixed set of standard properties as columns. The lines of `.log`
+/// files are split into columns according to a [`LogFormat`]. Syslog files (`.syslog`) and
+/// journal exports written by `journalctl -o export` (`.export`) are read with timestamp,
+/// unit, priority and message columns. With the `pdf` feature, ruled
+/// tables are extracted from PDF files on a best-effort basis. With the `sdmx` feature, the observations of SDMX-ML
+/// data messages are read from `.xml` and `.sdmx` files. SDMX-CSV files are plain CSV files
+/// and need no feature. The `shapefile` and `gpkg` features add reading the attribute tables
+/// of `.shp` files and GeoPackages (`.gpkg`) with the geometries as WKT, where GeoPackages
+/// are read like SQLite databases starting with their first feature table. With the `yaml`
+/// feature, `.yaml` and `.yml` files containing a sequence of mappings are read like JSON
+/// arrays.
+/// With the `sqlite` feature, a table or query result of SQLite databases (`.db`, `.sqlite`
+/// and `.sqlite3`) is read, see [`FileReader::table`] and [`FileReader::query`].
 /// The delimiter for CSV files can be specified.
 ///
 /// # Examples
@@ -39,11 +188,36 @@ impl FileFormat {
 /// ```
 pub struct FileReader {
     file_format: FileFormat,
-    file: BufReader<File>,
+    file: BufReader<Transcoder>,
+    columns: Option<Vec<usize>>,
+    error_policy: ErrorPolicy,
+    rejected_rows: Vec<u64>,
+    warnings: Vec<Warning>,
+    log_format: Option<LogFormat>,
+    csv_dialect: CsvDialect,
+    normalization: Normalization,
+    json_rendering: JsonRendering,
+    header_renaming: HeaderRenaming,
+    dedupe_headers: bool,
+    natural_sort: bool,
+    /// Whether the file is read from a stream that cannot be read twice.
+    streamed: bool,
+    #[cfg(feature = "sqlite")]
+    database: Option<sqlite::Database>,
+    #[cfg(feature = "bench")]
+    timings: bench::Timings,
 }
 
+/// A seekable input the records of a [`FileReader`] are read from.
+trait Source: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> Source for T {}
+
 impl FileReader {
     /// Creates a new FileReader instance.
+    /// Gzip compressed files ending with `.gz` are decompressed transparently.
+    /// The path `-` reads CSV with the given delimiter from standard input, see
+    /// [`FileReader::from_stdin`].
     ///
     /// # Examples
     ///
@@ -53,12 +227,340 @@ impl FileReader {
     /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
     /// ```
     pub fn new(file_path: &str, delimiter: Option<char>) -> Result<FileReader, FileError> {
-        let file_format = FileFormat::from_file(file_path, delimiter)?;
-        let file = BufReader::new(File::open(file_path)?);
-        Ok(FileReader { file_format, file })
+        FileReader::with_decompressors(file_path, delimiter, Vec::new())
+    }
+
+    /// Creates a new FileReader instance that transparently decompresses files compressed
+    /// with the given schemes in addition to the built-in ones. The compression schemes are
+    /// detected by the file extensions, so `data.csv.gz` is decompressed with [`Gzip`] and
+    /// then read as CSV. Stacked schemes like `data.csv.gz.enc` are applied outermost first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{FileReader, Gzip};
+    ///
+    /// let mut reader = FileReader::with_decompressors("tests/test.csv.gz", Some(','), vec![Box::new(Gzip)])
+    ///     .expect("Failed to create FileReader");
+    /// ```
+    pub fn with_decompressors(
+        file_path: &str,
+        delimiter: Option<char>,
+        decompressors: Vec<Box<dyn Decompressor>>,
+    ) -> Result<FileReader, FileError> {
+        if file_path == "-" {
+            let delimiter = delimiter.ok_or(FileError::UnknownFileFormat)?;
+            return FileReader::from_stdin(FileFormat::Csv(delimiter));
+        }
+        #[cfg(feature = "shapefile")]
+        if file_path.ends_with(".shp") {
+            return FileReader::from_table(shapefile::read_shapefile_table(std::path::Path::new(
+                file_path,
+            ))?);
+        }
+        #[cfg(feature = "bench")]
+        let start = std::time::Instant::now();
+        let mut available = decompressors;
+        available.extend(compression::default_decompressors());
+        let (path, decompressors) = compression::detect_compression(file_path, available);
+        let file_format = FileFormat::from_file(path, delimiter)?;
+        #[cfg(feature = "sqlite")]
+        if file_format == FileFormat::Sqlite && !decompressors.is_empty() {
+            return Err(FileError::CompressedDatabase(file_path.to_string()));
+        }
+        #[cfg(feature = "bench")]
+        let detection = start.elapsed();
+        let file: Box<dyn Source> = if decompressors.is_empty() {
+            Box::new(File::open(file_path)?)
+        } else {
+            Box::new(DecompressedSource::new(file_path.into(), decompressors)?)
+        };
+        #[allow(unused_mut)]
+        let mut reader = FileReader::from_source(file_format, file).check_vcard()?;
+        #[cfg(feature = "bench")]
+        reader.timings.detection.set(detection);
+        #[cfg(feature = "sqlite")]
+        if file_format == FileFormat::Sqlite {
+            reader.database = Some(sqlite::Database::new(file_path));
+        }
+        Ok(reader)
+    }
+
+    /// Creates a new FileReader instance for an input that is opened from its start by the
+    /// given function, like a member of an archive. The format and compression are detected
+    /// from the given file name and checked the same way as by [`FileReader::new`].
+    #[cfg(feature = "archive")]
+    pub(crate) fn from_opener(
+        file_name: &str,
+        delimiter: Option<char>,
+        open: compression::Opener,
+    ) -> Result<FileReader, FileError> {
+        let (path, decompressors) =
+            compression::detect_compression(file_name, compression::default_decompressors());
+        let file_format = FileFormat::from_file(path, delimiter)?;
+        #[cfg(feature = "sqlite")]
+        if file_format == FileFormat::Sqlite {
+            return Err(FileError::UnknownFileFormat);
+        }
+        let file = DecompressedSource::from_opener(open, decompressors)?;
+        FileReader::from_source(file_format, Box::new(file)).check_vcard()
+    }
+
+    /// Creates a new FileReader instance for a file located at a remote URL.
+    /// Supported are `http://`, `https://` and `s3://bucket/key` URLs, where the latter are
+    /// resolved to the public HTTPS endpoint of the bucket. The file format is derived from
+    /// the extension of the URL path. Requires the `remote` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::from_url("https://example.com/data.csv", Some(','))
+    ///     .expect("Failed to create FileReader");
+    /// ```
+    #[cfg(feature = "remote")]
+    pub fn from_url(url: &str, delimiter: Option<char>) -> Result<FileReader, FileError> {
+        let url = remote::resolve_url(url)?;
+        let file_format = FileFormat::from_file(remote::url_path(&url), delimiter)?;
+        #[cfg(feature = "sqlite")]
+        if file_format == FileFormat::Sqlite {
+            return Err(FileError::UnsupportedUrl(url));
+        }
+        FileReader::from_source(file_format, Box::new(io::Cursor::new(remote::fetch(&url)?)))
+            .check_vcard()
+    }
+
+    /// Creates a new FileReader instance that reads a file of the given format from standard
+    /// input, e.g. for `some_tool | datavzrd` pipelines. See [`FileReader::from_reader`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use readervzrd::{FileFormat, FileReader};
+    ///
+    /// let mut reader = FileReader::from_stdin(FileFormat::Csv('\t')).expect("Failed to create FileReader");
+    /// let headers = reader.headers().expect("Failed to get headers");
+    /// let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+    /// ```
+    pub fn from_stdin(file_format: FileFormat) -> Result<FileReader, FileError> {
+        FileReader::from_reader(io::stdin(), file_format)
+    }
+
+    /// Creates a new FileReader instance that reads a file of the given format from a reader
+    /// that does not need to support seeking.
+    /// CSV, newline-delimited JSON, log, syslog and journal files are read in a single
+    /// forward pass, keeping only the first MiB in memory so that the headers can be read
+    /// before the records. Reading the records once more, or anything else that reads more
+    /// than the first MiB twice, fails. The headers of newline-delimited JSON are collected
+    /// from the records in the first 64 KiB. Files of other formats are read into memory
+    /// completely. SQLite databases cannot be read from a reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{FileFormat, FileReader};
+    ///
+    /// let input = "{\"name\": \"John\", \"age\": 30}\n{\"name\": \"Alice\", \"age\": 25}\n";
+    /// let mut reader = FileReader::from_reader(input.as_bytes(), FileFormat::Ndjson)
+    ///     .expect("Failed to create FileReader");
+    /// assert_eq!(reader.headers().unwrap(), vec!["age", "name"]);
+    /// assert_eq!(reader.records().unwrap().count(), 2);
+    /// ```
+    pub fn from_reader<R: Read + Send + 'static>(
+        mut reader: R,
+        file_format: FileFormat,
+    ) -> Result<FileReader, FileError> {
+        let streamed = matches!(
+            file_format,
+            FileFormat::Csv(_)
+                | FileFormat::Ndjson
+                | FileFormat::Log
+                | FileFormat::Syslog
+                | FileFormat::Journal
+        );
+        #[cfg(feature = "sqlite")]
+        if file_format == FileFormat::Sqlite {
+            return Err(FileError::UnknownFileFormat);
+        }
+        let file: Box<dyn Source> = if streamed {
+            Box::new(StreamSource::new(Box::new(reader)))
+        } else {
+            let mut data = Vec::new();
+            reader.read_to_end(&mut data)?;
+            Box::new(io::Cursor::new(data))
+        };
+        let mut reader = FileReader::from_source(file_format, file);
+        reader.streamed = streamed;
+        Ok(reader)
+    }
+
+    /// Creates a FileReader instance for a table that has been read into memory completely,
+    /// whose first row contains the headers. The table is kept as CSV, which is always read
+    /// with the default dialect.
+    #[cfg(feature = "shapefile")]
+    fn from_table(table: Vec<Vec<String>>) -> Result<FileReader, FileError> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for row in table {
+            writer.write_record(row)?;
+        }
+        let data = writer.into_inner().map_err(|err| err.into_error())?;
+        let mut reader =
+            FileReader::from_source(FileFormat::Csv(','), Box::new(io::Cursor::new(data)));
+        reader.csv_dialect.fixed = true;
+        Ok(reader)
+    }
+
+    /// Returns whether [`FileReader::new`] supports the file, judged by its extensions and,
+    /// for vCards, its first bytes. The file is not kept open.
+    pub(crate) fn supports(file_path: &str, delimiter: Option<char>) -> Result<bool, FileError> {
+        #[cfg(feature = "shapefile")]
+        if file_path.ends_with(".shp") {
+            return Ok(true);
+        }
+        let (path, _) =
+            compression::detect_compression(file_path, compression::default_decompressors());
+        let file_format = match FileFormat::from_file(path, delimiter) {
+            Err(FileError::UnknownFileFormat) => return Ok(false),
+            file_format => file_format?,
+        };
+        if file_format != FileFormat::Vcard {
+            return Ok(true);
+        }
+        match FileReader::new(file_path, delimiter) {
+            Ok(_) => Ok(true),
+            Err(FileError::UnknownFileFormat) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Fails with [`FileError::UnknownFileFormat`] if a file detected as vCard by its
+    /// extension does not start like one, like Variant Call Format files (`.vcf`).
+    fn check_vcard(mut self) -> Result<FileReader, FileError> {
+        if self.file_format == FileFormat::Vcard && !vobject::is_vcard(self.file.fill_buf()?) {
+            return Err(FileError::UnknownFileFormat);
+        }
+        Ok(self)
+    }
+
+    fn from_source(file_format: FileFormat, file: Box<dyn Source>) -> FileReader {
+        FileReader {
+            file_format,
+            file: BufReader::new(Transcoder::new(file)),
+            columns: None,
+            error_policy: ErrorPolicy::default(),
+            rejected_rows: Vec::new(),
+            warnings: Vec::new(),
+            log_format: None,
+            csv_dialect: CsvDialect::default(),
+            normalization: Normalization::default(),
+            json_rendering: JsonRendering::default(),
+            header_renaming: HeaderRenaming::default(),
+            dedupe_headers: false,
+            natural_sort: false,
+            streamed: false,
+            #[cfg(feature = "sqlite")]
+            database: None,
+            #[cfg(feature = "bench")]
+            timings: bench::Timings::default(),
+        }
+    }
+
+    /// Sets the encoding of the file, which is transcoded to UTF-8 while reading.
+    /// The encoding is given by its label, e.g. `latin1`, `windows-1252` or `utf-16le`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/latin1_test.csv", Some(','))
+    ///     .expect("Failed to create FileReader")
+    ///     .encoding("latin1")
+    ///     .expect("Unknown encoding");
+    /// ```
+    pub fn encoding(mut self, label: &str) -> Result<FileReader, FileError> {
+        let encoding = encoding::encoding_for_label(label)
+            .ok_or_else(|| FileError::UnknownEncoding(label.to_string()))?;
+        let detect_bom = self.file.get_ref().detect_bom();
+        self.file.get_mut().configure(Some(encoding), detect_bom)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        Ok(self)
+    }
+
+    /// Sets whether a byte order mark at the start of the file determines its encoding.
+    /// UTF-8 and UTF-16 byte order marks are detected and removed, which takes precedence
+    /// over the encoding set with [`FileReader::encoding`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/utf16_test.csv", Some(','))
+    ///     .expect("Failed to create FileReader")
+    ///     .detect_bom(true)
+    ///     .expect("Failed to detect byte order mark");
+    /// assert_eq!(reader.headers().unwrap(), vec!["Name", "City"]);
+    /// ```
+    pub fn detect_bom(mut self, detect_bom: bool) -> Result<FileReader, FileError> {
+        let encoding = self.file.get_ref().encoding();
+        self.file.get_mut().configure(encoding, detect_bom)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        Ok(self)
+    }
+
+    /// Sets how malformed records are handled. Defaults to [`ErrorPolicy::Skip`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{ErrorPolicy, FileReader};
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(','))
+    ///     .expect("Failed to create FileReader")
+    ///     .error_policy(ErrorPolicy::Strict);
+    /// ```
+    pub fn error_policy(mut self, error_policy: ErrorPolicy) -> FileReader {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// Restricts the headers and records to the given columns in the given order.
+    /// Returns the given columns that are missing from the file, which are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let missing = reader.select_columns(&["Country", "Name", "Email"]).unwrap();
+    /// assert_eq!(missing, vec!["Email"]);
+    /// assert_eq!(reader.headers().unwrap(), vec!["Country", "Name"]);
+    /// ```
+    pub fn select_columns<S: AsRef<str>>(
+        &mut self,
+        columns: &[S],
+    ) -> Result<Vec<String>, FileError> {
+        self.columns = None;
+        let headers = self.headers()?;
+        let mut selected = Vec::new();
+        let mut missing = Vec::new();
+        for column in columns {
+            match headers.iter().position(|header| header == column.as_ref()) {
+                Some(index) => selected.push(index),
+                None => missing.push(column.as_ref().to_string()),
+            }
+        }
+        self.columns = Some(selected);
+        Ok(missing)
     }
 
     /// Returns the headers of the file.
+    /// Duplicate and empty headers are rejected with [`FileError::InvalidHeaders`] unless
+    /// they are renamed with [`FileReader::dedupe_headers`]. The first header may be empty,
+    /// like the row label column of labeled matrices.
     ///
     /// # Examples
     ///
@@ -69,40 +571,124 @@ impl FileReader {
     /// let headers = reader.headers().expect("Failed to get headers");
     /// ```
     pub fn headers(&mut self) -> Result<Vec<String>, FileError> {
-        match &self.file_format {
-            FileFormat::Csv(delimiter) => self.read_csv_headers(&delimiter.to_owned()),
-            FileFormat::Json => self.read_json_headers(),
-        }
+        #[cfg(feature = "bench")]
+        let start = std::time::Instant::now();
+        let headers = match &self.file_format {
+            FileFormat::Csv(delimiter) => self.read_csv_headers(*delimiter)?,
+            FileFormat::Json | FileFormat::Ndjson => self.read_json_headers()?,
+            FileFormat::GeoJson => self.read_geojson_headers()?,
+            FileFormat::Ics => vobject::ICS_COLUMNS.map(String::from).to_vec(),
+            FileFormat::Vcard => vobject::VCARD_COLUMNS.map(String::from).to_vec(),
+            FileFormat::Log => self.effective_log_format().headers(),
+            FileFormat::Syslog | FileFormat::Journal => {
+                syslog::SYSLOG_COLUMNS.map(String::from).to_vec()
+            }
+            #[cfg(feature = "pdf")]
+            FileFormat::Pdf => self.read_pdf_headers()?,
+            #[cfg(feature = "sdmx")]
+            FileFormat::Sdmx => self.read_sdmx_headers()?,
+            #[cfg(feature = "sqlite")]
+            FileFormat::Sqlite => self
+                .database
+                .as_ref()
+                .ok_or(FileError::UnknownFileFormat)?
+                .headers()?,
+            #[cfg(feature = "yaml")]
+            FileFormat::Yaml => self.read_yaml_headers()?,
+        };
+        #[cfg(feature = "bench")]
+        self.timings.headers.set(start.elapsed());
+        let headers = self.header_renaming.apply(headers);
+        let headers = if self.dedupe_headers {
+            headers::dedupe_headers(headers)
+        } else {
+            headers::validate_headers(&headers)?;
+            headers
+        };
+        Ok(match &self.columns {
+            Some(columns) => select_fields(columns, headers),
+            None => headers,
+        })
     }
 
-    fn read_csv_headers(&mut self, delimiter: &char) -> Result<Vec<String>, FileError> {
-        let mut reader = csv::ReaderBuilder::new()
-            .delimiter(*delimiter as u8)
-            .from_reader(&mut self.file);
-        let headers = reader
-            .headers()
-            .unwrap()
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
+    fn read_csv_headers(&mut self, delimiter: char) -> Result<Vec<String>, FileError> {
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut reader =
+            csv_reader_builder(delimiter, &self.csv_dialect).from_reader(&mut self.file);
+        let headers = reader.headers()?.iter().map(|s| s.to_string()).collect();
         self.file.seek(SeekFrom::Start(0))?;
         Ok(headers)
     }
 
     fn read_json_headers(&mut self) -> Result<Vec<String>, FileError> {
+        self.file.seek(SeekFrom::Start(0))?;
+        let limit = if self.streamed {
+            STREAMED_HEADER_BYTES
+        } else {
+            u64::MAX
+        };
         let mut headers = Vec::new();
-        if let Ok(serde_json::Value::Array(array)) = serde_json::from_reader(&mut self.file) {
-            for item in array {
-                if let serde_json::Value::Object(obj) = item {
-                    flatten_json_object(&mut headers, &obj, String::new());
-                }
+        for item in JsonItems::new((&mut self.file).take(limit)).filter_map(Result::ok) {
+            if let Value::Object(obj) = item {
+                self.json_rendering.headers(&mut headers, &obj);
+            }
+        }
+        self.file.seek(SeekFrom::Start(0))?;
+        Ok(headers)
+    }
+
+    #[cfg(feature = "yaml")]
+    fn read_yaml_headers(&mut self) -> Result<Vec<String>, FileError> {
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut text = String::new();
+        self.file.read_to_string(&mut text)?;
+        let mut headers = Vec::new();
+        for item in yaml::read_yaml_items(&text)? {
+            if let Value::Object(obj) = item {
+                self.json_rendering.headers(&mut headers, &obj);
             }
         }
         Ok(headers)
     }
 
+    fn read_geojson_headers(&mut self) -> Result<Vec<String>, FileError> {
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut text = String::new();
+        self.file.read_to_string(&mut text)?;
+        Ok(geojson::read_geojson_table(&text, "")?
+            .into_iter()
+            .next()
+            .unwrap_or_default())
+    }
+
+    #[cfg(feature = "pdf")]
+    fn read_pdf_headers(&mut self) -> Result<Vec<String>, FileError> {
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut data = Vec::new();
+        self.file.read_to_end(&mut data)?;
+        Ok(pdf::read_pdf_table(&data)?
+            .into_iter()
+            .next()
+            .unwrap_or_default())
+    }
+
+    #[cfg(feature = "sdmx")]
+    fn read_sdmx_headers(&mut self) -> Result<Vec<String>, FileError> {
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut text = String::new();
+        self.file.read_to_string(&mut text)?;
+        Ok(sdmx::read_sdmx_table(&text)?
+            .into_iter()
+            .next()
+            .unwrap_or_default())
+    }
+
     /// Returns an iterator over the records of the file.
     /// Each record is a vector of strings.
+    /// Malformed records are handled according to the [`ErrorPolicy`] of the reader.
+    /// With [`ErrorPolicy::Strict`] the iterator ends at the first malformed record, which is
+    /// reported as [`Warning::FailedRow`]. Use [`FileReader::try_records`] to retrieve the error
+    /// instead.
     ///
     /// # Examples
     ///
@@ -114,50 +700,482 @@ impl FileReader {
     ///    println!("{:?}", record);
     /// }
     /// ```
-    pub fn records(&mut self) -> Result<FlexRecordIter, FileError> {
-        match &self.file_format {
-            FileFormat::Csv(delimiter) => Ok(FlexRecordIter::Csv(Box::new(
-                self.read_csv_records(&delimiter.to_owned()),
-            ))),
-            FileFormat::Json => Ok(FlexRecordIter::Json(Box::new(self.read_json_records()?))),
-        }
+    pub fn records(&mut self) -> Result<FlexRecordIter<'_>, FileError> {
+        let file_format = self.file_format;
+        let records = self.try_records()?.map_while(Result::ok);
+        Ok(match file_format {
+            FileFormat::Csv(_) => FlexRecordIter::Csv(Box::new(records)),
+            FileFormat::Json | FileFormat::Ndjson | FileFormat::GeoJson => {
+                FlexRecordIter::Json(Box::new(records))
+            }
+            FileFormat::Ics => FlexRecordIter::Ics(Box::new(records)),
+            FileFormat::Vcard => FlexRecordIter::Vcard(Box::new(records)),
+            FileFormat::Log | FileFormat::Syslog | FileFormat::Journal => {
+                FlexRecordIter::Log(Box::new(records))
+            }
+            #[cfg(feature = "pdf")]
+            FileFormat::Pdf => FlexRecordIter::Pdf(Box::new(records)),
+            #[cfg(feature = "sdmx")]
+            FileFormat::Sdmx => FlexRecordIter::Sdmx(Box::new(records)),
+            #[cfg(feature = "sqlite")]
+            FileFormat::Sqlite => FlexRecordIter::Sqlite(Box::new(records)),
+            #[cfg(feature = "yaml")]
+            FileFormat::Yaml => FlexRecordIter::Json(Box::new(records)),
+        })
     }
 
-    fn read_csv_records<'a>(
-        &'a mut self,
-        delimiter: &char,
-    ) -> impl Iterator<Item = Vec<String>> + 'a {
-        let mut reader = csv::ReaderBuilder::new()
-            .delimiter(*delimiter as u8)
-            .from_reader(&mut self.file);
-        let records: Vec<Vec<String>> = reader
-            .records()
-            .filter_map(Result::ok)
-            .map(|record| record.iter().map(|field| field.to_string()).collect())
-            .collect();
-        self.file
-            .seek(SeekFrom::Start(0))
-            .expect("Failed to seek to start");
-        records.into_iter()
+    /// Returns an iterator over the records of the file that surfaces malformed records as
+    /// errors if the reader uses [`ErrorPolicy::Strict`]. The errors tell where the malformed
+    /// record is located in the file, see [`RecordError`].
+    /// With [`ErrorPolicy::Skip`] and [`ErrorPolicy::Collect`] malformed records are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{ErrorPolicy, FileReader};
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(','))
+    ///     .expect("Failed to create FileReader")
+    ///     .error_policy(ErrorPolicy::Strict);
+    /// for record in reader.try_records().unwrap() {
+    ///    let record = record.expect("Malformed record");
+    /// }
+    /// ```
+    pub fn try_records(
+        &mut self,
+    ) -> Result<impl Iterator<Item = Result<Vec<String>, RecordError>> + '_, FileError> {
+        self.try_records_from(0)
     }
 
+    /// Like [`FileReader::try_records`], but skips the given number of records first.
+    /// The skipped records are counted as they appear in the file, including malformed ones.
+    /// Skipped CSV records are not decoded.
+    fn try_records_from(
+        &mut self,
+        offset: usize,
+    ) -> Result<impl Iterator<Item = Result<Vec<String>, RecordError>> + '_, FileError> {
+        let width = self.flexible_width()?;
+        self.file.seek(SeekFrom::Start(0))?;
+        self.rejected_rows.clear();
+        self.warnings.clear();
+        let lossy = self.file.get_ref().lossy();
+        let log_format = self.effective_log_format();
+        #[cfg(feature = "sqlite")]
+        let database = self.database.clone();
+        let null = self.normalization.null_output().to_string();
+        let json_rendering = self.json_rendering.clone();
+        let FileReader {
+            file_format,
+            file,
+            columns,
+            error_policy,
+            rejected_rows,
+            warnings,
+            csv_dialect,
+            normalization,
+            #[cfg(feature = "bench")]
+            timings,
+            ..
+        } = self;
+        #[cfg(feature = "bench")]
+        let timings = &*timings;
+        #[cfg(feature = "bench")]
+        timings.reset_records();
+        #[cfg(feature = "bench")]
+        let start = std::time::Instant::now();
+        let records: RawRecords = match file_format {
+            FileFormat::Csv(delimiter) => {
+                Box::new(read_csv_records(file, *delimiter, csv_dialect, offset))
+            }
+            FileFormat::Json | FileFormat::Ndjson => {
+                Box::new(read_json_records(file, null, json_rendering).skip(offset))
+            }
+            FileFormat::GeoJson => Box::new(read_geojson_records(file, &null)?.skip(offset)),
+            FileFormat::Ics => {
+                Box::new(read_vobject_records(file, "VEVENT", &vobject::ICS_COLUMNS)?.skip(offset))
+            }
+            FileFormat::Vcard => {
+                Box::new(read_vobject_records(file, "VCARD", &vobject::VCARD_COLUMNS)?.skip(offset))
+            }
+            FileFormat::Log => Box::new(log::read_log_records(file, log_format).skip(offset)),
+            FileFormat::Syslog => Box::new(syslog::read_syslog_records(file).skip(offset)),
+            FileFormat::Journal => Box::new(syslog::read_journal_records(file).skip(offset)),
+            #[cfg(feature = "pdf")]
+            FileFormat::Pdf => Box::new(read_pdf_records(file)?.skip(offset)),
+            #[cfg(feature = "sdmx")]
+            FileFormat::Sdmx => Box::new(read_sdmx_records(file)?.skip(offset)),
+            #[cfg(feature = "sqlite")]
+            FileFormat::Sqlite => Box::new(
+                database
+                    .ok_or(FileError::UnknownFileFormat)?
+                    .rows(&null)?
+                    .skip(offset),
+            ),
+            #[cfg(feature = "yaml")]
+            FileFormat::Yaml => {
+                Box::new(read_yaml_records(file, &null, &json_rendering)?.skip(offset))
+            }
+        };
+        #[cfg(feature = "bench")]
+        bench::add_elapsed(&timings.decode, start);
+        #[cfg(feature = "bench")]
+        let records = bench::timed(records, &timings.decode);
+        let error_policy = *error_policy;
+        let columns = columns.clone();
+        let normalization = normalization.clone();
+        let mut next_record = offset;
+        Ok(records.filter_map(move |(line, record)| {
+            let record_number = next_record;
+            next_record += 1;
+            if lossy.load(Ordering::Relaxed) && !warnings.contains(&Warning::LossyDecoding) {
+                warnings.push(Warning::LossyDecoding);
+            }
+            match (record, error_policy) {
+                (Ok(record), _) => {
+                    #[cfg(feature = "bench")]
+                    let start = std::time::Instant::now();
+                    let (record, extra) =
+                        finish_record(record, width, columns.as_deref(), &normalization);
+                    if extra > 0 {
+                        warnings.push(Warning::ExtraColumns { line, count: extra });
+                    }
+                    #[cfg(feature = "bench")]
+                    bench::add_elapsed(&timings.stringify, start);
+                    Some(Ok(record))
+                }
+                (Err(err), ErrorPolicy::Strict) => {
+                    warnings.push(Warning::FailedRow {
+                        line,
+                        reason: err.to_string(),
+                    });
+                    Some(Err(RecordError::new(record_number, line, err)))
+                }
+                (Err(err), policy) => {
+                    if policy == ErrorPolicy::Collect {
+                        rejected_rows.push(line);
+                    }
+                    warnings.push(Warning::DroppedRow {
+                        line,
+                        reason: err.to_string(),
+                    });
+                    None
+                }
+            }
+        }))
+    }
+
+    /// Returns the line numbers of the malformed records that have been left out while
+    /// iterating over the records with [`ErrorPolicy::Collect`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{ErrorPolicy, FileReader};
+    ///
+    /// let mut reader = FileReader::new("tests/malformed_test.csv", Some(','))
+    ///     .expect("Failed to create FileReader")
+    ///     .error_policy(ErrorPolicy::Collect);
+    /// let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+    /// assert_eq!(reader.rejected_rows(), [3]);
+    /// ```
+    pub fn rejected_rows(&self) -> &[u64] {
+        &self.rejected_rows
+    }
+
+    /// Returns the issues encountered during the last iteration over the records, like
+    /// malformed records left out or stopped at according to the [`ErrorPolicy`] or malformed
+    /// input replaced while transcoding it to UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{FileReader, Warning};
+    ///
+    /// let mut reader = FileReader::new("tests/malformed_test.csv", Some(','))
+    ///     .expect("Failed to create FileReader");
+    /// let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+    /// assert!(matches!(reader.warnings(), [Warning::DroppedRow { line: 3, .. }]));
+    /// ```
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Returns the number of headers of a CSV file read with [`FileReader::flexible`], which
+    /// its records are fitted to.
+    fn flexible_width(&mut self) -> Result<Option<usize>, FileError> {
+        Ok(match self.file_format {
+            FileFormat::Csv(delimiter) if self.csv_dialect.flexible => {
+                Some(self.read_csv_headers(delimiter)?.len())
+            }
+            _ => None,
+        })
+    }
+
+    fn effective_log_format(&self) -> LogFormat {
+        self.log_format.clone().unwrap_or_else(LogFormat::combined)
+    }
+
+    /// Returns an iterator over the records of a JSON file, where nested objects are
+    /// flattened the same way as by [`FileReader::records`]. Malformed records are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.json", None).expect("Failed to create FileReader");
+    /// for record in reader.read_json_records().expect("Failed to read records") {
+    ///     println!("{:?}", record);
+    /// }
+    /// ```
     pub fn read_json_records(
         &mut self,
     ) -> Result<impl Iterator<Item = Vec<String>> + '_, FileError> {
-        let deserializer = Deserializer::from_reader(&mut self.file).into_iter::<Value>();
-        let iter = deserializer
-            .filter_map(Result::ok)
-            .flat_map(|value| match value {
-                Value::Array(arr) => arr.into_iter().map(flatten_json_record),
-                _ => panic!("Expected JSON array"),
-            });
+        let null = self.normalization.null_output().to_string();
+        let iter = read_json_records(&mut self.file, null, self.json_rendering.clone())
+            .filter_map(|(_, record)| record.ok());
         Ok(iter)
     }
 }
 
+/// The number of bytes at the start of streamed newline-delimited JSON files that headers are
+/// collected from.
+const STREAMED_HEADER_BYTES: u64 = 64 * 1024;
+
+/// An iterator over the records of a file together with their line numbers.
+type RawRecords<'a> = Box<dyn Iterator<Item = (u64, Result<Vec<String>, FileError>)> + 'a>;
+
+/// Returns an iterator over the records of a CSV file together with their line numbers.
+/// The given number of records is skipped without decoding them.
+fn read_csv_records<R: io::Read>(
+    file: R,
+    delimiter: char,
+    dialect: &CsvDialect,
+    offset: usize,
+) -> impl Iterator<Item = (u64, Result<Vec<String>, FileError>)> {
+    let mut reader = csv_reader_builder(delimiter, dialect).from_reader(file);
+    let mut skipped = csv::ByteRecord::new();
+    for _ in 0..offset {
+        if let Ok(false) = reader.read_byte_record(&mut skipped) {
+            break;
+        }
+    }
+    reader.into_records().map(|record| match record {
+        Ok(record) => (
+            record.position().map_or(0, |position| position.line()),
+            Ok(record.iter().map(|field| field.to_string()).collect()),
+        ),
+        Err(err) => (
+            err.position().map_or(0, |position| position.line()),
+            Err(err.into()),
+        ),
+    })
+}
+
+/// Returns an iterator over the records of a JSON file together with their line numbers.
+/// Null values are replaced with the given placeholder.
+fn read_json_records<R: io::BufRead>(
+    file: R,
+    null: String,
+    rendering: JsonRendering,
+) -> impl Iterator<Item = (u64, Result<Vec<String>, FileError>)> {
+    let mut items = JsonItems::new(file);
+    std::iter::from_fn(move || {
+        let item = items.next()?;
+        Some((items.line(), item))
+    })
+    .flat_map(move |(line, item)| match item {
+        Ok(item) => rendering
+            .render(item, &null)
+            .into_iter()
+            .map(|record| (line, Ok(record)))
+            .collect(),
+        Err(err) => vec![(line, Err(err))],
+    })
+}
+
+/// Returns an iterator over the items of a YAML file together with their numbers.
+/// Null values are replaced with the given placeholder.
+#[cfg(feature = "yaml")]
+fn read_yaml_records<R: Read>(
+    mut file: R,
+    null: &str,
+    rendering: &JsonRendering,
+) -> Result<impl Iterator<Item = (u64, Result<Vec<String>, FileError>)>, FileError> {
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+    let records: Vec<(u64, Result<Vec<String>, FileError>)> = yaml::read_yaml_items(&text)?
+        .into_iter()
+        .enumerate()
+        .flat_map(|(index, item)| {
+            rendering
+                .render(item, null)
+                .into_iter()
+                .map(move |record| (index as u64 + 1, Ok(record)))
+        })
+        .collect();
+    Ok(records.into_iter())
+}
+
+/// Returns an iterator over the records of a table whose first row contains the headers
+/// together with their row numbers, where the header row is row 1.
+fn table_records(
+    table: Vec<Vec<String>>,
+) -> impl Iterator<Item = (u64, Result<Vec<String>, FileError>)> {
+    table
+        .into_iter()
+        .enumerate()
+        .skip(1)
+        .map(|(index, record)| (index as u64 + 1, Ok(record)))
+}
+
+/// Returns an iterator over the features of a GeoJSON file together with their row numbers,
+/// where the header row is row 1.
+fn read_geojson_records<R: Read>(
+    mut file: R,
+    null: &str,
+) -> Result<impl Iterator<Item = (u64, Result<Vec<String>, FileError>)>, FileError> {
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+    Ok(table_records(geojson::read_geojson_table(&text, null)?))
+}
+
+/// Returns an iterator over the components of the given type in an iCalendar or vCard file
+/// together with their record numbers.
+fn read_vobject_records<R: Read>(
+    mut file: R,
+    component: &str,
+    columns: &[&str],
+) -> Result<impl Iterator<Item = (u64, Result<Vec<String>, FileError>)>, FileError> {
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+    Ok(vobject::read_components(&text, component, columns)
+        .into_iter()
+        .enumerate()
+        .map(|(index, record)| (index as u64 + 1, Ok(record))))
+}
+
+/// Returns an iterator over the records of the table in a PDF file together with their
+/// row numbers, where the header row is row 1.
+#[cfg(feature = "pdf")]
+fn read_pdf_records<R: Read>(
+    mut file: R,
+) -> Result<impl Iterator<Item = (u64, Result<Vec<String>, FileError>)>, FileError> {
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    Ok(table_records(pdf::read_pdf_table(&data)?))
+}
+
+/// Returns an iterator over the observations of an SDMX-ML data message together with their
+/// row numbers, where the header row is row 1.
+#[cfg(feature = "sdmx")]
+fn read_sdmx_records<R: Read>(
+    mut file: R,
+) -> Result<impl Iterator<Item = (u64, Result<Vec<String>, FileError>)>, FileError> {
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+    Ok(table_records(sdmx::read_sdmx_table(&text)?))
+}
+
+/// A malformed record surfaced by [`FileReader::try_records`], with its location in the file.
+#[derive(Error, Debug, PartialEq)]
+#[error("Malformed record {record} at line {line}: {source}")]
+pub struct RecordError {
+    record: usize,
+    line: u64,
+    byte_offset: Option<u64>,
+    source: FileError,
+}
+
+impl RecordError {
+    fn new(record: usize, line: u64, source: FileError) -> RecordError {
+        let byte_offset = match &source {
+            FileError::CsvError(err) => err.position().map(|position| position.byte()),
+            _ => None,
+        };
+        RecordError {
+            record,
+            line,
+            byte_offset,
+            source,
+        }
+    }
+
+    /// Returns the number of the record, counting all records of the file including
+    /// malformed ones from 0.
+    pub fn record(&self) -> usize {
+        self.record
+    }
+
+    /// Returns the line of the file the record starts at, counted from 1. For YAML files,
+    /// this is the number of the item in the sequence instead.
+    pub fn line(&self) -> u64 {
+        self.line
+    }
+
+    /// Returns the byte offset of the start of the record in the file, which is known for
+    /// CSV records.
+    pub fn byte_offset(&self) -> Option<u64> {
+        self.byte_offset
+    }
+
+    /// Returns the error the record could not be read with.
+    pub fn error(&self) -> &FileError {
+        &self.source
+    }
+
+    /// Returns the error the record could not be read with, consuming the record error.
+    pub fn into_error(self) -> FileError {
+        self.source
+    }
+}
+
+/// Defines how malformed records are handled while reading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Malformed records are surfaced as errors by [`FileReader::try_records`].
+    Strict,
+    /// Malformed records are silently left out.
+    #[default]
+    Skip,
+    /// Malformed records are left out and their line numbers are collected, which can be
+    /// retrieved with [`FileReader::rejected_rows`].
+    Collect,
+}
+
+/// A non-fatal issue encountered while reading records, see [`FileReader::warnings`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// A malformed record was left out. This includes CSV records with more or fewer fields
+    /// than the header unless [`FileReader::flexible`] is set.
+    DroppedRow { line: u64, reason: String },
+    /// A malformed record was returned as an error because of [`ErrorPolicy::Strict`].
+    /// [`FileReader::records`] and everything built on it end before this record.
+    FailedRow { line: u64, reason: String },
+    /// Malformed byte sequences were replaced with U+FFFD while transcoding the file to
+    /// UTF-8. Reported once per iteration.
+    LossyDecoding,
+    /// The given number of fields beyond the header of a CSV record have been left out, see
+    /// [`FileReader::flexible`].
+    ExtraColumns { line: u64, count: usize },
+}
+
+/// An iterator over the records of a file, see [`FileReader::records`]. Variants depend on
+/// the enabled features and may be added with new file formats.
+#[non_exhaustive]
 pub enum FlexRecordIter<'a> {
     Csv(Box<dyn Iterator<Item = Vec<String>> + 'a>),
     Json(Box<dyn Iterator<Item = Vec<String>> + 'a>),
+    Ics(Box<dyn Iterator<Item = Vec<String>> + 'a>),
+    Vcard(Box<dyn Iterator<Item = Vec<String>> + 'a>),
+    Log(Box<dyn Iterator<Item = Vec<String>> + 'a>),
+    #[cfg(feature = "pdf")]
+    Pdf(Box<dyn Iterator<Item = Vec<String>> + 'a>),
+    #[cfg(feature = "sdmx")]
+    Sdmx(Box<dyn Iterator<Item = Vec<String>> + 'a>),
+    #[cfg(feature = "sqlite")]
+    Sqlite(Box<dyn Iterator<Item = Vec<String>> + 'a>),
 }
 
 impl<'a> Iterator for FlexRecordIter<'a> {
@@ -167,53 +1185,50 @@ impl<'a> Iterator for FlexRecordIter<'a> {
         match self {
             FlexRecordIter::Csv(iter) => iter.next(),
             FlexRecordIter::Json(iter) => iter.next(),
+            FlexRecordIter::Ics(iter) => iter.next(),
+            FlexRecordIter::Vcard(iter) => iter.next(),
+            FlexRecordIter::Log(iter) => iter.next(),
+            #[cfg(feature = "pdf")]
+            FlexRecordIter::Pdf(iter) => iter.next(),
+            #[cfg(feature = "sdmx")]
+            FlexRecordIter::Sdmx(iter) => iter.next(),
+            #[cfg(feature = "sqlite")]
+            FlexRecordIter::Sqlite(iter) => iter.next(),
         }
     }
 }
 
-fn flatten_json_record(value: Value) -> Vec<String> {
-    match value {
-        Value::String(s) => vec![s],
-        Value::Number(n) => vec![n.to_string()],
-        Value::Array(a) => vec![serde_json::to_string(&a).unwrap()],
-        Value::Object(obj) => obj
-            .into_iter()
-            .flat_map(|(_, v)| flatten_json_record(v))
-            .collect(),
-        _ => unreachable!("Unexpected value type"),
+/// Fits a record to the given width of a flexible CSV file by filling in empty values or
+/// leaving out extra fields, and applies the column selection and normalization.
+/// Returns the record together with the number of extra fields left out.
+fn finish_record(
+    mut record: Vec<String>,
+    width: Option<usize>,
+    columns: Option<&[usize]>,
+    normalization: &Normalization,
+) -> (Vec<String>, usize) {
+    let mut extra = 0;
+    if let Some(width) = width {
+        extra = record.len().saturating_sub(width);
+        record.resize(width, String::new());
     }
+    let record = normalization.apply(match columns {
+        Some(columns) => select_fields(columns, record),
+        None => record,
+    });
+    (record, extra)
 }
 
-fn flatten_json_object(
-    headers: &mut Vec<String>,
-    obj: &serde_json::Map<String, Value>,
-    prefix: String,
-) {
-    for (key, value) in obj {
-        match value {
-            Value::Object(inner_obj) => {
-                let new_prefix = if prefix.is_empty() {
-                    key.to_string()
-                } else {
-                    format!("{}.{}", prefix, key)
-                };
-                flatten_json_object(headers, inner_obj, new_prefix);
-            }
-            _ => {
-                let header = if prefix.is_empty() {
-                    key.to_string()
-                } else {
-                    format!("{}.{}", prefix, key)
-                };
-                if !headers.contains(&header) {
-                    headers.push(header);
-                }
-            }
-        }
-    }
+/// Returns the fields at the given indices. Missing fields are returned as empty strings.
+fn select_fields(indices: &[usize], fields: Vec<String>) -> Vec<String> {
+    indices
+        .iter()
+        .map(|index| fields.get(*index).cloned().unwrap_or_default())
+        .collect()
 }
 
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum FileError {
     #[error("Unknown file format")]
     UnknownFileFormat,
@@ -221,6 +1236,72 @@ pub enum FileError {
     InvalidJsonStructure,
     #[error("IO error: {0}")]
     IoError(#[from] io::Error),
+    #[error("CSV error: {0}")]
+    CsvError(#[from] csv::Error),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Invalid matrix value '{value}' in row {row}, column '{column}'")]
+    InvalidMatrixValue {
+        row: usize,
+        column: String,
+        value: String,
+    },
+    #[error("Row {0} does not match the number of matrix columns")]
+    InvalidMatrixShape(usize),
+    #[error("Failed to deserialize record {row}: {source}")]
+    DeserializeError {
+        row: usize,
+        source: csv::DeserializeError,
+    },
+    #[error("Record {0} does not match the number of headers")]
+    InvalidRecordLength(usize),
+    #[error("Headers of {0} do not match the headers of the other files")]
+    HeaderMismatch(String),
+    #[error("File format of {0} does not match the format of the other files")]
+    FormatMismatch(String),
+    #[error("Invalid glob pattern: {0}")]
+    InvalidGlob(String),
+    #[error("No files given or matching the pattern")]
+    NoFiles,
+    #[error("Unknown encoding: {0}")]
+    UnknownEncoding(String),
+    #[error("Unsupported URL: {0}")]
+    UnsupportedUrl(String),
+    #[error("Unknown column: {0}")]
+    UnknownColumn(String),
+    #[error("Invalid headers: {0}")]
+    InvalidHeaders(String),
+    #[error("Invalid filter: {0}")]
+    InvalidFilter(String),
+    #[error("Invalid geometry: {0}")]
+    InvalidGeometry(String),
+    #[error("Invalid log pattern: {0}")]
+    InvalidLogPattern(String),
+    #[error("Line {0} does not match the log format")]
+    UnmatchedLogLine(u64),
+    #[error("Compressed SQLite databases are not supported: {0}")]
+    CompressedDatabase(String),
+    #[cfg(feature = "arrow")]
+    #[error("Arrow error: {0}")]
+    ArrowError(#[from] arrow::error::ArrowError),
+    #[cfg(feature = "pdf")]
+    #[error("PDF error: {0}")]
+    PdfError(#[from] lopdf::Error),
+    #[cfg(feature = "remote")]
+    #[error("Request error: {0}")]
+    RequestError(#[from] Box<ureq::Error>),
+    #[cfg(feature = "sdmx")]
+    #[error("XML error: {0}")]
+    XmlError(#[from] roxmltree::Error),
+    #[cfg(feature = "archive")]
+    #[error("Archive error: {0}")]
+    ArchiveError(#[from] zip::result::ZipError),
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    SqliteError(#[from] rusqlite::Error),
+    #[cfg(feature = "yaml")]
+    #[error("YAML error: {0}")]
+    YamlError(#[from] serde_yaml::Error),
 }
 
 impl PartialEq for FileError {
@@ -229,6 +1310,47 @@ impl PartialEq for FileError {
             (FileError::UnknownFileFormat, FileError::UnknownFileFormat) => true,
             (FileError::InvalidJsonStructure, FileError::InvalidJsonStructure) => true,
             (FileError::IoError(e1), FileError::IoError(e2)) => e1.kind() == e2.kind(),
+            (FileError::CsvError(e1), FileError::CsvError(e2)) => e1.to_string() == e2.to_string(),
+            (FileError::JsonError(e1), FileError::JsonError(e2)) => {
+                e1.to_string() == e2.to_string()
+            }
+            (
+                FileError::InvalidMatrixValue {
+                    row: r1,
+                    column: c1,
+                    value: v1,
+                },
+                FileError::InvalidMatrixValue {
+                    row: r2,
+                    column: c2,
+                    value: v2,
+                },
+            ) => r1 == r2 && c1 == c2 && v1 == v2,
+            (FileError::InvalidMatrixShape(r1), FileError::InvalidMatrixShape(r2)) => r1 == r2,
+            (
+                FileError::DeserializeError {
+                    row: r1,
+                    source: s1,
+                },
+                FileError::DeserializeError {
+                    row: r2,
+                    source: s2,
+                },
+            ) => r1 == r2 && s1.to_string() == s2.to_string(),
+            (FileError::InvalidRecordLength(r1), FileError::InvalidRecordLength(r2)) => r1 == r2,
+            (FileError::HeaderMismatch(p1), FileError::HeaderMismatch(p2)) => p1 == p2,
+            (FileError::FormatMismatch(p1), FileError::FormatMismatch(p2)) => p1 == p2,
+            (FileError::InvalidGlob(p1), FileError::InvalidGlob(p2)) => p1 == p2,
+            (FileError::NoFiles, FileError::NoFiles) => true,
+            (FileError::UnknownEncoding(l1), FileError::UnknownEncoding(l2)) => l1 == l2,
+            (FileError::UnsupportedUrl(u1), FileError::UnsupportedUrl(u2)) => u1 == u2,
+            (FileError::UnknownColumn(c1), FileError::UnknownColumn(c2)) => c1 == c2,
+            (FileError::InvalidFilter(f1), FileError::InvalidFilter(f2)) => f1 == f2,
+            (FileError::InvalidGeometry(g1), FileError::InvalidGeometry(g2)) => g1 == g2,
+            (FileError::InvalidHeaders(h1), FileError::InvalidHeaders(h2)) => h1 == h2,
+            (FileError::InvalidLogPattern(p1), FileError::InvalidLogPattern(p2)) => p1 == p2,
+            (FileError::UnmatchedLogLine(l1), FileError::UnmatchedLogLine(l2)) => l1 == l2,
+            (FileError::CompressedDatabase(p1), FileError::CompressedDatabase(p2)) => p1 == p2,
             (_, _) => false,
         }
     }
@@ -266,6 +1388,18 @@ mod tests {
         assert_eq!(records.len(), 3);
     }
 
+    #[test]
+    fn test_json_headers_does_not_drain_records() {
+        let mut reader =
+            FileReader::new("tests/test.json", None).expect("Failed to create FileReader");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        let headers = reader.headers().expect("Failed to get headers");
+        let records_again: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(headers, vec!["age", "country", "name"]);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records_again, records);
+    }
+
     #[test]
     fn test_json_headers() {
         let mut reader =
@@ -285,6 +1419,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ndjson_headers() {
+        let mut reader =
+            FileReader::new("tests/test.ndjson", None).expect("Failed to create FileReader");
+        let headers = reader.headers().expect("Failed to get headers");
+        assert_eq!(headers, vec!["age", "country", "name"]);
+    }
+
+    #[test]
+    fn test_ndjson_records() {
+        let mut reader =
+            FileReader::new("tests/test.ndjson", None).expect("Failed to create FileReader");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0], vec!["30", "USA", "John"]);
+        assert_eq!(records[1], vec!["25", "UK", "Alice"]);
+        assert_eq!(records[2], vec!["40", "Canada", "Bob"]);
+    }
+
+    #[test]
+    fn test_geojson_records() {
+        let mut reader =
+            FileReader::new("tests/test.geojson", None).expect("Failed to create FileReader");
+        let headers = reader.headers().expect("Failed to get headers");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(
+            headers,
+            vec!["address.city", "capital", "name", "population", "geometry"]
+        );
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0][4], "POINT (13.405 52.52)");
+    }
+
+    #[cfg(feature = "shapefile")]
+    #[test]
+    fn test_shapefile_records() {
+        let mut reader =
+            FileReader::new("tests/parks.shp", None).expect("Failed to create FileReader");
+        let headers = reader.headers().expect("Failed to get headers");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(headers, vec!["NAME", "AREA", "OPEN", "geometry"]);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1][3], "POLYGON ((20 0, 25 5, 30 0, 20 0))");
+        let mut reader = FileReader::new("tests/parks.shp", None)
+            .expect("Failed to create FileReader")
+            .comment(Some('S'))
+            .quote('(');
+        assert_eq!(reader.records().unwrap().collect::<Vec<_>>(), records);
+    }
+
+    #[test]
+    fn test_variant_call_format_is_not_vcard() {
+        let path = std::env::temp_dir().join("readervzrd_variants.vcf");
+        std::fs::write(&path, "##fileformat=VCFv4.2\n#CHROM\tPOS\n").unwrap();
+        assert_eq!(
+            FileReader::new(path.to_str().unwrap(), None).err(),
+            Some(FileError::UnknownFileFormat)
+        );
+        std::fs::remove_file(&path).unwrap();
+        let mut reader =
+            FileReader::new("tests/test.vcf", None).expect("Failed to create FileReader");
+        assert_eq!(reader.records().unwrap().count(), 2);
+    }
+
     #[test]
     fn test_csv_records() {
         let mut reader =
@@ -329,6 +1527,174 @@ mod tests {
         assert_eq!(records[2], vec!["Bob", "40", "Canada"]);
     }
 
+    #[test]
+    fn test_select_columns() {
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        let missing = reader
+            .select_columns(&["Country", "Email", "Name"])
+            .expect("Failed to select columns");
+        let headers = reader.headers().expect("Failed to get headers");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(missing, vec!["Email"]);
+        assert_eq!(headers, vec!["Country", "Name"]);
+        assert_eq!(records[0], vec!["USA", "John"]);
+        assert_eq!(records[2], vec!["Canada", "Bob"]);
+    }
+
+    #[test]
+    fn test_select_nested_json_columns() {
+        let mut reader =
+            FileReader::new("tests/nested_test.json", None).expect("Failed to create FileReader");
+        let missing = reader
+            .select_columns(&["name", "bank.institution"])
+            .expect("Failed to select columns");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert!(missing.is_empty());
+        assert_eq!(records[1], vec!["Alice", "Barclays"]);
+    }
+
+    #[test]
+    fn test_error_policy_skip() {
+        let mut reader = FileReader::new("tests/malformed_test.csv", Some(','))
+            .expect("Failed to create FileReader");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1], vec!["Bob", "40", "Canada"]);
+        assert!(reader.rejected_rows().is_empty());
+    }
+
+    #[test]
+    fn test_error_policy_strict() {
+        let mut reader = FileReader::new("tests/malformed_test.csv", Some(','))
+            .expect("Failed to create FileReader")
+            .error_policy(ErrorPolicy::Strict);
+        let records: Vec<Result<Vec<String>, RecordError>> =
+            reader.try_records().unwrap().collect();
+        assert_eq!(records.len(), 3);
+        let error = records[1].as_ref().unwrap_err();
+        assert!(matches!(error.error(), FileError::CsvError(_)));
+        assert_eq!((error.record(), error.line()), (1, 3));
+        assert_eq!(error.byte_offset(), Some(29));
+        assert_eq!(reader.records().unwrap().count(), 1);
+        assert!(matches!(
+            reader.warnings(),
+            [Warning::FailedRow { line: 3, .. }]
+        ));
+    }
+
+    #[test]
+    fn test_error_policy_collect() {
+        let mut reader = FileReader::new("tests/malformed_test.json", None)
+            .expect("Failed to create FileReader")
+            .error_policy(ErrorPolicy::Collect);
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(reader.rejected_rows(), [7]);
+    }
+
+    #[test]
+    fn test_lossy_decoding_warning() {
+        let mut reader = FileReader::new("tests/latin1_test.csv", Some(','))
+            .expect("Failed to create FileReader")
+            .encoding("utf-8")
+            .expect("Unknown encoding");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records[0], vec!["J\u{fffd}rgen", "M\u{fffd}nchen"]);
+        assert_eq!(reader.warnings(), [Warning::LossyDecoding]);
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        let _: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert!(reader.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_gzip_csv() {
+        let mut reader =
+            FileReader::new("tests/test.csv.gz", Some(',')).expect("Failed to create FileReader");
+        let headers = reader.headers().expect("Failed to get headers");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(headers, vec!["Name", "Age", "Country"]);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2], vec!["Bob", "40", "Canada"]);
+    }
+
+    #[test]
+    fn test_custom_decompressor() {
+        struct Xor;
+
+        struct XorReader(Box<dyn Read + Send>);
+
+        impl Read for XorReader {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let len = self.0.read(buf)?;
+                buf[..len].iter_mut().for_each(|b| *b ^= 0x2a);
+                Ok(len)
+            }
+        }
+
+        impl Decompressor for Xor {
+            fn extension(&self) -> &str {
+                "xor"
+            }
+
+            fn decompress(&self, reader: Box<dyn Read + Send>) -> io::Result<Box<dyn Read + Send>> {
+                Ok(Box::new(XorReader(reader)))
+            }
+        }
+
+        let path = std::env::temp_dir().join("readervzrd_test.json.xor");
+        let data: Vec<u8> = std::fs::read("tests/test.json")
+            .unwrap()
+            .into_iter()
+            .map(|b| b ^ 0x2a)
+            .collect();
+        std::fs::write(&path, data).unwrap();
+        let mut reader =
+            FileReader::with_decompressors(path.to_str().unwrap(), None, vec![Box::new(Xor)])
+                .expect("Failed to create FileReader");
+        assert_eq!(
+            reader.headers().expect("Failed to get headers"),
+            vec!["age", "country", "name"]
+        );
+        assert_eq!(reader.records().unwrap().count(), 3);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_latin1_csv() {
+        let mut reader = FileReader::new("tests/latin1_test.csv", Some(','))
+            .expect("Failed to create FileReader")
+            .encoding("latin1")
+            .expect("Unknown encoding");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records[0], vec!["Jürgen", "München"]);
+        assert_eq!(records[1], vec!["Zoë", "Besançon"]);
+    }
+
+    #[test]
+    fn test_utf16_csv_with_bom() {
+        let mut reader = FileReader::new("tests/utf16_test.csv", Some(','))
+            .expect("Failed to create FileReader")
+            .detect_bom(true)
+            .expect("Failed to detect byte order mark");
+        let headers = reader.headers().expect("Failed to get headers");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(headers, vec!["Name", "City"]);
+        assert_eq!(records[1], vec!["Zoë", "Besançon"]);
+    }
+
+    #[test]
+    fn test_unknown_encoding() {
+        let result = FileReader::new("tests/test.csv", Some(','))
+            .expect("Failed to create FileReader")
+            .encoding("klingon");
+        assert_eq!(
+            result.err().unwrap(),
+            FileError::UnknownEncoding("klingon".to_string())
+        );
+    }
+
     #[test]
     fn test_unknown_file_format() {
         let result = FileReader::new("tests/test.txt", None);
@@ -347,6 +1713,20 @@ mod tests {
         assert_eq!(records[2], vec!["40", "Canada", "Bob", "[]"]);
     }
 
+    #[test]
+    fn test_json_records_with_null() {
+        let mut reader =
+            FileReader::new("tests/null_test.json", None).expect("Failed to create FileReader");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records[0], vec!["1", "", "NA"]);
+        let mut reader = FileReader::new("tests/null_test.json", None)
+            .expect("Failed to create FileReader")
+            .null_values(&["NA"])
+            .null_output(".");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records, vec![vec!["1", ".", "."], vec!["2", "Alice", ""]]);
+    }
+
     #[test]
     fn test_json_headers_with_inner_array() {
         let mut reader = FileReader::new("tests/inner_array_test.json", None)
@@ -366,6 +1746,12 @@ mod tests {
         assert_eq!(records[2], vec!["40", "Canada", "Bob"]);
     }
 
+    #[test]
+    fn test_file_reader_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<FileReader>();
+    }
+
     #[test]
     fn test_partial_eq_file_error() {
         assert_eq!(FileError::UnknownFileFormat, FileError::UnknownFileFormat);
@@ -0,0 +1,190 @@
+use crate::{FileError, FileReader};
+use regex::Regex;
+use std::io::BufRead;
+
+/// The fields of a line in Common Log Format.
+const COMMON: &str = r#"^(?P<host>\S+) (?P<ident>\S+) (?P<user>\S+) \[(?P<time>[^\]]*)\] "(?P<request>(?:[^"\\]|\\.)*)" (?P<status>\d{3}|-) (?P<size>\d+|-)"#;
+/// The additional fields of a line in Combined Log Format.
+const COMBINED: &str = r#"(?: "(?P<referer>(?:[^"\\]|\\.)*)" "(?P<user_agent>(?:[^"\\]|\\.)*)")?"#;
+
+/// Describes how the lines of a log file are split into fields.
+/// Each named capture group of the underlying regular expression becomes a column.
+///
+/// # Examples
+///
+/// ```
+/// use readervzrd::{FileReader, LogFormat};
+///
+/// let format = LogFormat::pattern(r"^(?P<level>\w+): (?P<message>.*)$").expect("Invalid pattern");
+/// let mut reader = FileReader::new("tests/access.log", None)
+///     .expect("Failed to create FileReader")
+///     .log_format(format);
+/// assert_eq!(reader.headers().unwrap(), vec!["level", "message"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LogFormat {
+    regex: Regex,
+}
+
+impl LogFormat {
+    /// The Common Log Format of web servers with the columns `host`, `ident`, `user`, `time`,
+    /// `request`, `status` and `size`.
+    pub fn common() -> LogFormat {
+        LogFormat {
+            regex: Regex::new(COMMON).unwrap(),
+        }
+    }
+
+    /// The Combined Log Format of web servers, which adds the columns `referer` and
+    /// `user_agent` to the [`LogFormat::common`] columns. Lines in Common Log Format are
+    /// accepted as well and leave these columns empty.
+    pub fn combined() -> LogFormat {
+        LogFormat {
+            regex: Regex::new(&format!("{}{}", COMMON, COMBINED)).unwrap(),
+        }
+    }
+
+    /// A custom format given by a regular expression with named capture groups.
+    pub fn pattern(pattern: &str) -> Result<LogFormat, FileError> {
+        let regex =
+            Regex::new(pattern).map_err(|err| FileError::InvalidLogPattern(err.to_string()))?;
+        if regex.capture_names().flatten().next().is_none() {
+            return Err(FileError::InvalidLogPattern(
+                "no named capture groups".to_string(),
+            ));
+        }
+        Ok(LogFormat { regex })
+    }
+
+    pub(crate) fn headers(&self) -> Vec<String> {
+        self.regex
+            .capture_names()
+            .flatten()
+            .map(String::from)
+            .collect()
+    }
+}
+
+impl FileReader {
+    /// Sets the format of the lines of a `.log` file. Defaults to [`LogFormat::combined`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{FileReader, LogFormat};
+    ///
+    /// let mut reader = FileReader::new("tests/access.log", None)
+    ///     .expect("Failed to create FileReader")
+    ///     .log_format(LogFormat::common());
+    /// ```
+    pub fn log_format(mut self, log_format: LogFormat) -> FileReader {
+        self.log_format = Some(log_format);
+        self
+    }
+}
+
+/// Returns an iterator over the records of a log file together with their line numbers.
+/// Empty lines are skipped, lines not matching the format are malformed records.
+pub(crate) fn read_log_records<R: BufRead>(
+    file: R,
+    log_format: LogFormat,
+) -> impl Iterator<Item = (u64, Result<Vec<String>, FileError>)> {
+    file.lines()
+        .enumerate()
+        .map(|(index, line)| (index as u64 + 1, line))
+        .filter(|(_, line)| !line.as_ref().is_ok_and(|line| line.trim().is_empty()))
+        .map(move |(number, line)| {
+            let record = line.map_err(FileError::from).and_then(|line| {
+                let captures = log_format
+                    .regex
+                    .captures(&line)
+                    .ok_or(FileError::UnmatchedLogLine(number))?;
+                Ok(log_format
+                    .regex
+                    .capture_names()
+                    .flatten()
+                    .map(|name| {
+                        captures
+                            .name(name)
+                            .map_or_else(String::new, |field| field.as_str().to_string())
+                    })
+                    .collect())
+            });
+            (number, record)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorPolicy, RecordError};
+
+    #[test]
+    fn test_read_combined_log() {
+        let mut reader =
+            FileReader::new("tests/access.log", None).expect("Failed to create FileReader");
+        assert_eq!(
+            reader.headers().unwrap(),
+            vec![
+                "host",
+                "ident",
+                "user",
+                "time",
+                "request",
+                "status",
+                "size",
+                "referer",
+                "user_agent"
+            ]
+        );
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0],
+            vec![
+                "127.0.0.1",
+                "-",
+                "frank",
+                "10/Oct/2000:13:55:36 -0700",
+                "GET /apache_pb.gif HTTP/1.0",
+                "200",
+                "2326",
+                "http://www.example.com/start.html",
+                "Mozilla/4.08 [en] (Win98; I ;Nav)"
+            ]
+        );
+        assert_eq!(records[1][4], "POST /login HTTP/1.1");
+        assert_eq!(records[1][7], "");
+    }
+
+    #[test]
+    fn test_unmatched_log_line() {
+        let mut reader = FileReader::new("tests/access.log", None)
+            .expect("Failed to create FileReader")
+            .log_format(LogFormat::common())
+            .error_policy(ErrorPolicy::Collect);
+        assert_eq!(reader.records().unwrap().count(), 2);
+        assert_eq!(reader.rejected_rows(), [4]);
+        let mut reader = FileReader::new("tests/access.log", None)
+            .expect("Failed to create FileReader")
+            .error_policy(ErrorPolicy::Strict);
+        let error = reader
+            .try_records()
+            .unwrap()
+            .find_map(Result::err)
+            .map(RecordError::into_error);
+        assert_eq!(error, Some(FileError::UnmatchedLogLine(4)));
+    }
+
+    #[test]
+    fn test_invalid_log_pattern() {
+        assert!(matches!(
+            LogFormat::pattern(r"(\w+)"),
+            Err(FileError::InvalidLogPattern(_))
+        ));
+        assert!(matches!(
+            LogFormat::pattern(r"(?P<a>"),
+            Err(FileError::InvalidLogPattern(_))
+        ));
+    }
+}
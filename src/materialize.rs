@@ -0,0 +1,298 @@
+use crate::{FileError, FileReader};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// The number of distinct values beyond which a column is stored as plain text. Until then,
+/// the distinct values are kept twice while building the dictionary, once for the lookup.
+const DICTIONARY_LIMIT: usize = 4096;
+
+/// A table kept in memory by [`FileReader::materialize`] for repeated queries.
+/// Columns of integers or floats are stored as numbers and columns with few distinct values,
+/// at most 4096, are dictionary-encoded, which takes much less memory than the values as
+/// strings.
+/// All values are returned exactly as they were read.
+#[derive(Debug, Clone)]
+pub struct MaterializedTable {
+    headers: Vec<String>,
+    columns: Vec<Column>,
+    len: usize,
+}
+
+impl MaterializedTable {
+    /// Returns the headers of the table.
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// Returns the number of records in the table.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the table has no records.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the value in the given row and column, both counted from 0.
+    pub fn value(&self, row: usize, column: usize) -> Option<Cow<'_, str>> {
+        if row >= self.len {
+            return None;
+        }
+        Some(self.columns.get(column)?.value(row))
+    }
+
+    /// Returns the record in the given row, counted from 0.
+    pub fn record(&self, row: usize) -> Option<Vec<String>> {
+        (row < self.len).then(|| {
+            self.columns
+                .iter()
+                .map(|column| column.value(row).into_owned())
+                .collect()
+        })
+    }
+
+    /// Returns an iterator over the records of the table.
+    pub fn records(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        (0..self.len).filter_map(|row| self.record(row))
+    }
+}
+
+impl FileReader {
+    /// Reads all records into a [`MaterializedTable`], which can be queried repeatedly
+    /// without reading the file again. Records are aligned to the headers, missing values
+    /// are empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let table = reader.materialize().unwrap();
+    /// assert_eq!(table.len(), 3);
+    /// assert_eq!(table.value(1, 1).unwrap(), "25");
+    /// assert_eq!(table.record(2).unwrap(), vec!["Bob", "40", "Canada"]);
+    /// ```
+    pub fn materialize(&mut self) -> Result<MaterializedTable, FileError> {
+        let headers = self.headers()?;
+        let mut columns = vec![Column::Integer(Vec::new()); headers.len()];
+        let mut len = 0;
+        for record in self.records()? {
+            let mut values = record.into_iter();
+            for column in columns.iter_mut() {
+                column.push(values.next().unwrap_or_default());
+            }
+            len += 1;
+        }
+        Ok(MaterializedTable {
+            headers,
+            columns: columns.into_iter().map(Column::finish).collect(),
+            len,
+        })
+    }
+}
+
+/// The values of a column, stored as compact as they can be restored exactly.
+/// Empty values of numeric columns are `None`.
+#[derive(Debug, Clone, PartialEq)]
+enum Column {
+    Integer(Vec<Option<i64>>),
+    Float(Vec<Option<f64>>),
+    Dictionary {
+        values: Vec<String>,
+        codes: Vec<u32>,
+        lookup: HashMap<String, u32>,
+    },
+    Text(Vec<String>),
+}
+
+impl Column {
+    fn value(&self, row: usize) -> Cow<'_, str> {
+        match self {
+            Column::Integer(values) => {
+                values[row].map_or(Cow::Borrowed(""), |value| Cow::Owned(value.to_string()))
+            }
+            Column::Float(values) => {
+                values[row].map_or(Cow::Borrowed(""), |value| Cow::Owned(value.to_string()))
+            }
+            Column::Dictionary { values, codes, .. } => Cow::Borrowed(&values[codes[row] as usize]),
+            Column::Text(values) => Cow::Borrowed(&values[row]),
+        }
+    }
+
+    /// Appends a value, switching to a more general representation if the value does not
+    /// fit the current one.
+    fn push(&mut self, value: String) {
+        match self {
+            Column::Integer(values) => {
+                if value.is_empty() {
+                    return values.push(None);
+                }
+                if let Some(integer) = parse_exact::<i64>(&value) {
+                    return values.push(Some(integer));
+                }
+                let floats: Option<Vec<Option<f64>>> = values
+                    .iter()
+                    .map(|integer| match integer {
+                        Some(integer) => parse_exact::<f64>(&integer.to_string()).map(Some),
+                        None => Some(None),
+                    })
+                    .collect();
+                match floats {
+                    Some(floats) if parse_exact::<f64>(&value).is_some() => {
+                        *self = Column::Float(floats)
+                    }
+                    _ => self.generalize(),
+                }
+                self.push(value)
+            }
+            Column::Float(values) => {
+                if value.is_empty() {
+                    return values.push(None);
+                }
+                if let Some(float) = parse_exact::<f64>(&value) {
+                    return values.push(Some(float));
+                }
+                self.generalize();
+                self.push(value)
+            }
+            Column::Dictionary {
+                values,
+                codes,
+                lookup,
+            } => {
+                if values.len() == DICTIONARY_LIMIT && !lookup.contains_key(&value) {
+                    *self = std::mem::replace(self, Column::Text(Vec::new())).into_text();
+                    return self.push(value);
+                }
+                let code = *lookup.entry(value).or_insert_with_key(|value| {
+                    values.push(value.clone());
+                    values.len() as u32 - 1
+                });
+                codes.push(code);
+            }
+            Column::Text(values) => values.push(value),
+        }
+    }
+
+    /// Switches a numeric column to a dictionary of its values.
+    fn generalize(&mut self) {
+        let len = match self {
+            Column::Integer(values) => values.len(),
+            Column::Float(values) => values.len(),
+            _ => return,
+        };
+        let values: Vec<String> = (0..len).map(|row| self.value(row).into_owned()).collect();
+        *self = Column::Dictionary {
+            values: Vec::new(),
+            codes: Vec::new(),
+            lookup: HashMap::new(),
+        };
+        for value in values {
+            self.push(value);
+        }
+    }
+
+    /// Stores a dictionary as plain text.
+    fn into_text(self) -> Column {
+        match self {
+            Column::Dictionary { values, codes, .. } => Column::Text(
+                codes
+                    .into_iter()
+                    .map(|code| values[code as usize].clone())
+                    .collect(),
+            ),
+            column => column,
+        }
+    }
+
+    /// Stores dictionaries with mostly distinct values as plain text and drops the lookup
+    /// table that is only needed while building the column.
+    fn finish(self) -> Column {
+        match self {
+            Column::Dictionary {
+                ref values,
+                ref codes,
+                ..
+            } if values.len() * 2 > codes.len() => self.into_text(),
+            Column::Dictionary { values, codes, .. } => Column::Dictionary {
+                values,
+                codes,
+                lookup: HashMap::new(),
+            },
+            column => column,
+        }
+    }
+}
+
+/// Parses a number if it is written exactly as it would be formatted, so that the value can
+/// be restored from the number.
+fn parse_exact<T: std::str::FromStr + ToString>(value: &str) -> Option<T> {
+    value
+        .parse::<T>()
+        .ok()
+        .filter(|number| number.to_string() == value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Column {
+        let mut column = Column::Integer(Vec::new());
+        for value in values {
+            column.push(value.to_string());
+        }
+        column.finish()
+    }
+
+    #[test]
+    fn test_column_encodings() {
+        assert_eq!(
+            column(&["1", "", "-3"]),
+            Column::Integer(vec![Some(1), None, Some(-3)])
+        );
+        assert_eq!(
+            column(&["1", "2.5", ""]),
+            Column::Float(vec![Some(1.0), Some(2.5), None])
+        );
+        assert!(matches!(
+            column(&["a", "b", "a", "a"]),
+            Column::Dictionary { values, .. } if values == ["a", "b"]
+        ));
+        assert_eq!(
+            column(&["1", "01", "x"]),
+            Column::Text(vec!["1".to_string(), "01".to_string(), "x".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_dictionary_limit() {
+        let mut column = Column::Integer(Vec::new());
+        for value in 0..DICTIONARY_LIMIT * 2 {
+            column.push(format!("v{}", value % DICTIONARY_LIMIT));
+        }
+        assert!(
+            matches!(&column, Column::Dictionary { values, .. } if values.len() == DICTIONARY_LIMIT)
+        );
+        column.push("v".to_string());
+        assert!(
+            matches!(&column, Column::Text(values) if values.len() == DICTIONARY_LIMIT * 2 + 1)
+        );
+        assert_eq!(column.value(DICTIONARY_LIMIT + 1), "v1");
+    }
+
+    #[test]
+    fn test_materialize_roundtrip() {
+        let mut reader = FileReader::new("tests/variants.tsv", Some('\t'))
+            .expect("Failed to create FileReader")
+            .comment(Some('#'));
+        let table = reader.materialize().unwrap();
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(table.records().collect::<Vec<_>>(), records);
+        assert_eq!(table.headers(), ["chrom", "pos", "qual"]);
+        assert!(matches!(table.columns[1], Column::Integer(_)));
+        assert_eq!(table.value(2, 0), None);
+    }
+}
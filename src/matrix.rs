@@ -0,0 +1,149 @@
+use crate::{FileError, FileReader};
+
+/// A numeric matrix with labeled rows and columns, e.g. read from a wide CSV/TSV table
+/// whose first column holds the row labels.
+///
+/// # Examples
+///
+/// ```
+/// use readervzrd::FileReader;
+///
+/// let mut reader = FileReader::new("tests/matrix.tsv", Some('\t')).expect("Failed to create FileReader");
+/// let matrix = reader.matrix().expect("Failed to read matrix");
+/// assert_eq!(matrix.row_labels(), ["BRCA1", "TP53", "EGFR"]);
+/// assert_eq!(matrix.get(0, 1), Some(2.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    row_labels: Vec<String>,
+    column_labels: Vec<String>,
+    values: Vec<f64>,
+}
+
+impl Matrix {
+    /// Returns the labels of the rows, taken from the first column of the file.
+    pub fn row_labels(&self) -> &[String] {
+        &self.row_labels
+    }
+
+    /// Returns the labels of the columns, i.e. all headers except the row label column.
+    pub fn column_labels(&self) -> &[String] {
+        &self.column_labels
+    }
+
+    /// Returns all values of the matrix in row-major order.
+    /// Missing values (empty cells or `NA`) are represented as `NaN`.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Returns the values of the row at the given index.
+    pub fn row(&self, row: usize) -> Option<&[f64]> {
+        let width = self.column_labels.len();
+        self.values.get(row * width..(row + 1) * width)
+    }
+
+    /// Returns the value at the given row and column index.
+    pub fn get(&self, row: usize, column: usize) -> Option<f64> {
+        if column >= self.column_labels.len() {
+            return None;
+        }
+        self.row(row).map(|values| values[column])
+    }
+}
+
+impl FileReader {
+    /// Reads the file as a numeric matrix. The first column is used as row labels and the
+    /// remaining headers as column labels. All other cells have to be numeric, empty or `NA`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/matrix.tsv", Some('\t')).expect("Failed to create FileReader");
+    /// let matrix = reader.matrix().expect("Failed to read matrix");
+    /// assert_eq!(matrix.column_labels(), ["sample_a", "sample_b", "sample_c"]);
+    /// ```
+    pub fn matrix(&mut self) -> Result<Matrix, FileError> {
+        let column_labels: Vec<String> = self.headers()?.into_iter().skip(1).collect();
+        let mut row_labels = Vec::new();
+        let mut values = Vec::new();
+        for (row, record) in self.records()?.enumerate() {
+            let mut fields = record.into_iter();
+            row_labels.push(fields.next().unwrap_or_default());
+            let mut width = 0;
+            for (column, field) in fields.enumerate() {
+                values.push(
+                    parse_matrix_value(&field).ok_or(FileError::InvalidMatrixValue {
+                        row,
+                        column: column_labels.get(column).cloned().unwrap_or_default(),
+                        value: field.clone(),
+                    })?,
+                );
+                width += 1;
+            }
+            if width != column_labels.len() {
+                return Err(FileError::InvalidMatrixShape(row));
+            }
+        }
+        Ok(Matrix {
+            row_labels,
+            column_labels,
+            values,
+        })
+    }
+}
+
+fn parse_matrix_value(value: &str) -> Option<f64> {
+    match value.trim() {
+        "" | "NA" => Some(f64::NAN),
+        value => value.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileFormat;
+
+    #[test]
+    fn test_matrix() {
+        let mut reader =
+            FileReader::new("tests/matrix.tsv", Some('\t')).expect("Failed to create FileReader");
+        let matrix = reader.matrix().expect("Failed to read matrix");
+        assert_eq!(matrix.row_labels(), ["BRCA1", "TP53", "EGFR"]);
+        assert_eq!(matrix.column_labels(), ["sample_a", "sample_b", "sample_c"]);
+        assert_eq!(matrix.values().len(), 9);
+        assert_eq!(matrix.row(0), Some(&[1.5, 2.0, -0.25][..]));
+        assert!(matrix.get(1, 1).unwrap().is_nan());
+        assert!(matrix.get(2, 1).unwrap().is_nan());
+        assert_eq!(matrix.get(2, 2), Some(0.001));
+        assert_eq!(matrix.get(3, 0), None);
+        assert_eq!(matrix.get(0, 3), None);
+    }
+
+    #[test]
+    fn test_matrix_without_row_label_header() {
+        let mut reader =
+            FileReader::from_reader(&b"\tS1\tS2\nG1\t1\t2\n"[..], FileFormat::Csv('\t'))
+                .expect("Failed to create FileReader");
+        let matrix = reader.matrix().unwrap();
+        assert_eq!(matrix.column_labels(), ["S1", "S2"]);
+        assert_eq!(matrix.get(0, 1), Some(2.0));
+    }
+
+    #[test]
+    fn test_matrix_with_invalid_value() {
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        assert_eq!(
+            reader.matrix().err().unwrap(),
+            FileError::InvalidMatrixValue {
+                row: 0,
+                column: "Country".to_string(),
+                value: "USA".to_string(),
+            }
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{FileError, FileFormat, FileReader, RecordError};
+
+/// A reader over multiple files of the same format, e.g. the partitions of a table written
+/// as `results/part-*.csv`. The records of all files are exposed as a single table.
+/// By default the headers of all files have to match. With [`MultiFileReader::unify_headers`]
+/// the headers are unified instead and missing fields are filled with empty strings.
+/// Files are only opened once their records are read.
+///
+/// # Examples
+///
+/// ```
+/// use readervzrd::MultiFileReader;
+///
+/// let mut reader = MultiFileReader::from_glob("tests/parts/part-*.csv", Some(','))
+///     .expect("Failed to create MultiFileReader");
+/// let headers = reader.headers().expect("Failed to get headers");
+/// let records: Vec<Vec<String>> = reader.records().unwrap().collect::<Result<_, _>>().unwrap();
+/// ```
+pub struct MultiFileReader {
+    paths: Vec<String>,
+    /// The readers of the files whose records have been read, by position in `paths`.
+    readers: Vec<Option<FileReader>>,
+    /// The headers of the files that have been read, by position in `paths`.
+    file_headers: Vec<Option<Vec<String>>>,
+    delimiter: Option<char>,
+    unify_headers: bool,
+}
+
+impl MultiFileReader {
+    /// Creates a new MultiFileReader instance for the given files.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::MultiFileReader;
+    ///
+    /// let mut reader = MultiFileReader::new(&["tests/parts/part-1.csv", "tests/parts/part-2.csv"], Some(','))
+    ///     .expect("Failed to create MultiFileReader");
+    /// ```
+    pub fn new<P: AsRef<str>>(
+        file_paths: &[P],
+        delimiter: Option<char>,
+    ) -> Result<MultiFileReader, FileError> {
+        let mut file_format = None;
+        let mut paths = Vec::new();
+        for path in file_paths {
+            let path = path.as_ref();
+            let format = FileFormat::from_file(path, delimiter)?;
+            if *file_format.get_or_insert(format) != format {
+                return Err(FileError::FormatMismatch(path.to_string()));
+            }
+            std::fs::metadata(path)?;
+            paths.push(path.to_string());
+        }
+        if paths.is_empty() {
+            return Err(FileError::NoFiles);
+        }
+        Ok(MultiFileReader {
+            readers: paths.iter().map(|_| None).collect(),
+            file_headers: paths.iter().map(|_| None).collect(),
+            paths,
+            delimiter,
+            unify_headers: false,
+        })
+    }
+
+    /// Creates a new MultiFileReader instance for all files matching the given glob pattern.
+    /// The files are read in lexicographical order of their paths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::MultiFileReader;
+    ///
+    /// let mut reader = MultiFileReader::from_glob("tests/parts/part-*.csv", Some(','))
+    ///     .expect("Failed to create MultiFileReader");
+    /// ```
+    pub fn from_glob(pattern: &str, delimiter: Option<char>) -> Result<MultiFileReader, FileError> {
+        let mut paths = glob::glob(pattern)
+            .map_err(|_| FileError::InvalidGlob(pattern.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| FileError::IoError(err.into()))?
+            .into_iter()
+            .filter(|path| path.is_file())
+            .filter_map(|path| path.to_str().map(|path| path.to_string()))
+            .collect::<Vec<_>>();
+        paths.sort();
+        MultiFileReader::new(&paths, delimiter)
+    }
+
+    /// Sets whether differing headers are unified instead of rejected.
+    /// Unified headers contain every header of all files in order of their first occurrence.
+    pub fn unify_headers(mut self, unify_headers: bool) -> MultiFileReader {
+        self.unify_headers = unify_headers;
+        self
+    }
+
+    /// Returns the paths of the files read by this reader.
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    /// Returns the headers shared by all files.
+    pub fn headers(&mut self) -> Result<Vec<String>, FileError> {
+        Ok(self.all_headers()?.0)
+    }
+
+    /// Returns the headers shared by all files together with the headers of each file.
+    /// The headers of each file are only read once and the file is closed afterwards.
+    fn all_headers(&mut self) -> Result<(Vec<String>, Vec<Vec<String>>), FileError> {
+        let mut headers: Option<Vec<String>> = None;
+        let mut all_file_headers = Vec::new();
+        for (path, cached) in self.paths.iter().zip(self.file_headers.iter_mut()) {
+            let file_headers = match cached {
+                Some(file_headers) => file_headers.clone(),
+                None => cached
+                    .insert(FileReader::new(path, self.delimiter)?.headers()?)
+                    .clone(),
+            };
+            match headers.as_mut() {
+                None => headers = Some(file_headers.clone()),
+                Some(headers) if self.unify_headers => {
+                    for header in &file_headers {
+                        if !headers.contains(header) {
+                            headers.push(header.clone());
+                        }
+                    }
+                }
+                Some(headers) if *headers != file_headers => {
+                    return Err(FileError::HeaderMismatch(path.to_string()))
+                }
+                Some(_) => {}
+            }
+            all_file_headers.push(file_headers);
+        }
+        Ok((headers.unwrap_or_default(), all_file_headers))
+    }
+
+    /// Returns an iterator over the records of all files, one file after another.
+    /// Each record is aligned to the headers returned by [`MultiFileReader::headers`].
+    /// A file is opened once its first record is requested and kept open until the reader is
+    /// dropped. Iteration stops after an error opening or reading a file, which is returned as
+    /// the last item.
+    pub fn records(
+        &mut self,
+    ) -> Result<impl Iterator<Item = Result<Vec<String>, FileError>> + '_, FileError> {
+        let (headers, file_headers) = self.all_headers()?;
+        let width = headers.len();
+        let delimiter = self.delimiter;
+        let files = self
+            .paths
+            .iter()
+            .zip(self.readers.iter_mut())
+            .zip(file_headers);
+        let records = files.flat_map(move |((path, reader), file_headers)| {
+            let positions: Vec<usize> = file_headers
+                .iter()
+                .filter_map(|header| headers.iter().position(|h| h == header))
+                .collect();
+            let reader = match reader {
+                Some(reader) => Ok(reader),
+                None => FileReader::new(path, delimiter).map(|opened| reader.insert(opened)),
+            };
+            file_records(reader).map(move |record| {
+                record.map(|record| {
+                    let mut aligned = vec![String::new(); width];
+                    for (position, field) in positions.iter().zip(record) {
+                        aligned[*position] = field;
+                    }
+                    aligned
+                })
+            })
+        });
+        Ok(until_error(records))
+    }
+}
+
+/// Returns the records of the given reader, or the error it could not be opened with.
+pub(crate) fn file_records<'a>(
+    reader: Result<&'a mut FileReader, FileError>,
+) -> Box<dyn Iterator<Item = Result<Vec<String>, FileError>> + 'a> {
+    match reader.and_then(|reader| reader.try_records()) {
+        Ok(records) => Box::new(records.map(|record| record.map_err(RecordError::into_error))),
+        Err(err) => Box::new(std::iter::once(Err(err))),
+    }
+}
+
+/// Ends the given iterator after its first error.
+pub(crate) fn until_error<T>(
+    records: impl Iterator<Item = Result<T, FileError>>,
+) -> impl Iterator<Item = Result<T, FileError>> {
+    let mut failed = false;
+    records.map_while(move |record| {
+        if failed {
+            return None;
+        }
+        failed = record.is_err();
+        Some(record)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_file_records() {
+        let mut reader = MultiFileReader::from_glob("tests/parts/part-*.csv", Some(','))
+            .expect("Failed to create MultiFileReader");
+        assert_eq!(
+            reader.paths(),
+            ["tests/parts/part-1.csv", "tests/parts/part-2.csv"]
+        );
+        let headers = reader.headers().expect("Failed to get headers");
+        let records: Vec<Vec<String>> =
+            reader.records().unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(headers, vec!["Name", "Age", "Country"]);
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0], vec!["John", "30", "USA"]);
+        assert_eq!(records[4], vec!["Dave", "28", "France"]);
+    }
+
+    #[test]
+    fn test_multi_file_header_mismatch() {
+        let mut reader = MultiFileReader::new(
+            &["tests/parts/part-1.csv", "tests/parts/extra.csv"],
+            Some(','),
+        )
+        .expect("Failed to create MultiFileReader");
+        assert_eq!(
+            reader.headers().err().unwrap(),
+            FileError::HeaderMismatch("tests/parts/extra.csv".to_string())
+        );
+    }
+
+    #[test]
+    fn test_multi_file_unified_headers() {
+        let mut reader = MultiFileReader::new(
+            &["tests/parts/part-1.csv", "tests/parts/extra.csv"],
+            Some(','),
+        )
+        .expect("Failed to create MultiFileReader")
+        .unify_headers(true);
+        let headers = reader.headers().expect("Failed to get headers");
+        let records: Vec<Vec<String>> =
+            reader.records().unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(headers, vec!["Name", "Age", "Country", "City"]);
+        assert_eq!(records[0], vec!["John", "30", "USA", ""]);
+        assert_eq!(records[3], vec!["Erin", "", "Spain", "Madrid"]);
+    }
+
+    #[test]
+    fn test_unreadable_partition() {
+        let directory = std::env::temp_dir().join("readervzrd_multi_unreadable_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        for part in ["part-1.csv", "part-2.csv"] {
+            std::fs::copy(format!("tests/parts/{}", part), directory.join(part)).unwrap();
+        }
+        let pattern = directory.join("part-*.csv");
+        let mut reader = MultiFileReader::from_glob(pattern.to_str().unwrap(), Some(','))
+            .expect("Failed to create MultiFileReader");
+        reader.headers().expect("Failed to get headers");
+        std::fs::remove_file(directory.join("part-2.csv")).unwrap();
+        let records: Vec<Result<Vec<String>, FileError>> = reader.records().unwrap().collect();
+        assert_eq!(records.len(), 4);
+        assert!(records[..3].iter().all(Result::is_ok));
+        assert!(matches!(records[3], Err(FileError::IoError(_))));
+    }
+
+    #[test]
+    fn test_multi_file_errors() {
+        assert_eq!(
+            MultiFileReader::new(&["tests/test.csv", "tests/test.json"], Some(','))
+                .err()
+                .unwrap(),
+            FileError::FormatMismatch("tests/test.json".to_string())
+        );
+        assert_eq!(
+            MultiFileReader::from_glob("tests/parts/missing-*.csv", Some(','))
+                .err()
+                .unwrap(),
+            FileError::NoFiles
+        );
+        assert_eq!(
+            MultiFileReader::from_glob("tests/parts/[", Some(','))
+                .err()
+                .unwrap(),
+            FileError::InvalidGlob("tests/parts/[".to_string())
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use crate::FileReader;
+
+/// How the values of records are rewritten after reading, see [`FileReader::null_values`],
+/// [`FileReader::null_output`] and [`FileReader::decimal_separator`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Normalization {
+    null_values: Vec<String>,
+    null_output: String,
+    decimal_separator: Option<char>,
+}
+
+impl Normalization {
+    /// Returns the placeholder for missing values.
+    pub(crate) fn null_output(&self) -> &str {
+        &self.null_output
+    }
+
+    pub(crate) fn apply(&self, record: Vec<String>) -> Vec<String> {
+        record
+            .into_iter()
+            .map(|value| {
+                if self.null_values.contains(&value) {
+                    return self.null_output.clone();
+                }
+                self.decimal_separator
+                    .and_then(|separator| normalize_number(&value, separator))
+                    .unwrap_or(value)
+            })
+            .collect()
+    }
+}
+
+impl FileReader {
+    /// Sets values that denote missing values, like `NA`, `NULL` or `.`. They are replaced
+    /// with the placeholder set by [`FileReader::null_output`] in the records. Include the
+    /// empty string to replace empty values as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(','))
+    ///     .expect("Failed to create FileReader")
+    ///     .null_values(&["UK"]);
+    /// let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+    /// assert_eq!(records[1], vec!["Alice", "25", ""]);
+    /// ```
+    pub fn null_values<S: AsRef<str>>(mut self, null_values: &[S]) -> FileReader {
+        self.normalization.null_values = null_values
+            .iter()
+            .map(|value| value.as_ref().to_string())
+            .collect();
+        self
+    }
+
+    /// Sets the placeholder emitted for missing values. Defaults to the empty string.
+    /// Missing values are the values set by [`FileReader::null_values`] as well as real
+    /// nulls of the file format, i.e. `null` in JSON and YAML files and GeoJSON properties,
+    /// absent properties of GeoJSON features and `NULL` in SQLite databases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/null_test.json", None)
+    ///     .expect("Failed to create FileReader")
+    ///     .null_values(&["NA", ""])
+    ///     .null_output("NULL");
+    /// let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+    /// assert_eq!(records[0], vec!["1", "NULL", "NULL"]);
+    /// ```
+    pub fn null_output(mut self, null_output: &str) -> FileReader {
+        self.normalization.null_output = null_output.to_string();
+        self
+    }
+
+    /// Sets the decimal separator of numbers in the file, e.g. `,` for files written with a
+    /// German locale. Numbers are rewritten to use `.` as decimal separator and have their
+    /// digit grouping removed, so `1.234,5` becomes `1234.5`. Other values are kept as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/excel_test.csv", Some(';'))
+    ///     .expect("Failed to create FileReader")
+    ///     .decimal_separator(',');
+    /// let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+    /// assert_eq!(records[0][1], "1234.5");
+    /// ```
+    pub fn decimal_separator(mut self, decimal_separator: char) -> FileReader {
+        self.normalization.decimal_separator = Some(decimal_separator);
+        self
+    }
+}
+
+/// Rewrites a number with the given decimal separator and the other one of `.` and `,` as
+/// digit grouping to the plain notation with `.` as decimal separator.
+/// Returns `None` if the value is not such a number.
+fn normalize_number(value: &str, separator: char) -> Option<String> {
+    let grouping = if separator == ',' { '.' } else { ',' };
+    let (sign, unsigned) = match value.strip_prefix(['-', '+']) {
+        Some(unsigned) => (&value[..1], unsigned),
+        None => ("", value),
+    };
+    let (integer, fraction) = match unsigned.split_once(separator) {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+    let digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    let mut groups = integer.split(grouping);
+    let first = groups.next()?;
+    let mut grouped = false;
+    let mut normalized = format!("{}{}", sign, first);
+    for group in groups {
+        if group.len() != 3 || !digits(group) {
+            return None;
+        }
+        grouped = true;
+        normalized.push_str(group);
+    }
+    if !digits(first) || (grouped && first.len() > 3) || !fraction.is_none_or(digits) {
+        return None;
+    }
+    if let Some(fraction) = fraction {
+        normalized.push('.');
+        normalized.push_str(fraction);
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_number() {
+        assert_eq!(normalize_number("1.234,5", ','), Some("1234.5".to_string()));
+        assert_eq!(normalize_number("-0,25", ','), Some("-0.25".to_string()));
+        assert_eq!(normalize_number("12", ','), Some("12".to_string()));
+        assert_eq!(normalize_number("1,234.5", '.'), Some("1234.5".to_string()));
+        assert_eq!(normalize_number("1.5", ','), None);
+        assert_eq!(normalize_number("1234.567,8", ','), None);
+        assert_eq!(normalize_number("1,", ','), None);
+        assert_eq!(normalize_number("abc", ','), None);
+    }
+
+    #[test]
+    fn test_null_values() {
+        let normalization = Normalization {
+            null_values: vec!["NA".to_string(), ".".to_string()],
+            null_output: "-".to_string(),
+            decimal_separator: None,
+        };
+        assert_eq!(
+            normalization.apply(vec!["NA".to_string(), "1".to_string(), ".".to_string()]),
+            vec!["-", "1", "-"]
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use crate::FileError;
+use lopdf::content::Operation;
+use lopdf::{Document, Encoding, Object, ObjectId};
+use std::collections::BTreeMap;
+
+/// Maximum distance in points between ruling lines that are considered the same line.
+const TOLERANCE: f32 = 2.0;
+
+/// Extracts the rows of the ruled tables in the given PDF document on a best-effort basis.
+/// Tables are recognized by their horizontal and vertical ruling lines, text inside the grid
+/// formed by these lines is assigned to the enclosing cell. The first row is treated as the
+/// header row and repetitions of it on subsequent pages are skipped, as are tables whose
+/// number of columns differs from the first table.
+pub(crate) fn read_pdf_table(data: &[u8]) -> Result<Vec<Vec<String>>, FileError> {
+    let document = Document::load_mem(data)?;
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for page_id in document.get_pages().into_values() {
+        for row in read_page_table(&document, page_id)? {
+            match rows.first() {
+                None => rows.push(row),
+                Some(header) if header.len() == row.len() && *header != row => rows.push(row),
+                Some(_) => {}
+            }
+        }
+    }
+    Ok(rows)
+}
+
+fn read_page_table(document: &Document, page_id: ObjectId) -> Result<Vec<Vec<String>>, FileError> {
+    let encodings: BTreeMap<Vec<u8>, Encoding> = document
+        .get_page_fonts(page_id)?
+        .into_iter()
+        .filter_map(|(name, font)| Some((name, font.get_font_encoding(document).ok()?)))
+        .collect();
+    let content = document.get_and_decode_page_content(page_id)?;
+    let mut page = PageInterpreter::new(&encodings);
+    for operation in &content.operations {
+        page.apply(operation);
+    }
+    Ok(page.table())
+}
+
+/// A 2D affine transformation matrix `[a b c d e f]` as used by PDF content streams.
+#[derive(Debug, Clone, Copy)]
+struct Matrix([f32; 6]);
+
+impl Matrix {
+    const IDENTITY: Matrix = Matrix([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    fn translation(x: f32, y: f32) -> Matrix {
+        Matrix([1.0, 0.0, 0.0, 1.0, x, y])
+    }
+
+    /// Returns the transformation applying `self` first and `other` second.
+    fn then(&self, other: &Matrix) -> Matrix {
+        let [a, b, c, d, e, f] = self.0;
+        let [a2, b2, c2, d2, e2, f2] = other.0;
+        Matrix([
+            a * a2 + b * c2,
+            a * b2 + b * d2,
+            c * a2 + d * c2,
+            c * b2 + d * d2,
+            e * a2 + f * c2 + e2,
+            e * b2 + f * d2 + f2,
+        ])
+    }
+
+    fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let [a, b, c, d, e, f] = self.0;
+        (a * x + c * y + e, b * x + d * y + f)
+    }
+}
+
+/// A piece of text positioned at its origin in page coordinates.
+struct TextChunk {
+    x: f32,
+    y: f32,
+    text: String,
+}
+
+/// Interprets the operations of a page content stream that are relevant for tables,
+/// i.e. stroked or filled lines and text.
+struct PageInterpreter<'a> {
+    encodings: &'a BTreeMap<Vec<u8>, Encoding<'a>>,
+    ctm: Matrix,
+    ctm_stack: Vec<Matrix>,
+    path: Vec<(f32, f32, f32, f32)>,
+    current_point: (f32, f32),
+    subpath_start: (f32, f32),
+    text_matrix: Matrix,
+    line_matrix: Matrix,
+    leading: f32,
+    encoding: Option<&'a Encoding<'a>>,
+    horizontal: Vec<(f32, f32, f32)>,
+    vertical: Vec<(f32, f32, f32)>,
+    chunks: Vec<TextChunk>,
+}
+
+impl<'a> PageInterpreter<'a> {
+    fn new(encodings: &'a BTreeMap<Vec<u8>, Encoding<'a>>) -> PageInterpreter<'a> {
+        PageInterpreter {
+            encodings,
+            ctm: Matrix::IDENTITY,
+            ctm_stack: Vec::new(),
+            path: Vec::new(),
+            current_point: (0.0, 0.0),
+            subpath_start: (0.0, 0.0),
+            text_matrix: Matrix::IDENTITY,
+            line_matrix: Matrix::IDENTITY,
+            leading: 0.0,
+            encoding: None,
+            horizontal: Vec::new(),
+            vertical: Vec::new(),
+            chunks: Vec::new(),
+        }
+    }
+
+    fn apply(&mut self, operation: &Operation) {
+        let operands: Vec<f32> = operation
+            .operands
+            .iter()
+            .filter_map(|operand| operand.as_float().ok())
+            .collect();
+        match (operation.operator.as_str(), operands.as_slice()) {
+            ("q", _) => self.ctm_stack.push(self.ctm),
+            ("Q", _) => self.ctm = self.ctm_stack.pop().unwrap_or(Matrix::IDENTITY),
+            ("cm", &[a, b, c, d, e, f]) => self.ctm = Matrix([a, b, c, d, e, f]).then(&self.ctm),
+            ("m", &[x, y]) => {
+                self.current_point = (x, y);
+                self.subpath_start = (x, y);
+            }
+            ("l", &[x, y]) => {
+                let (x1, y1) = self.current_point;
+                self.path.push((x1, y1, x, y));
+                self.current_point = (x, y);
+            }
+            ("h", _) => {
+                let (x1, y1) = self.current_point;
+                let (x2, y2) = self.subpath_start;
+                self.path.push((x1, y1, x2, y2));
+                self.current_point = self.subpath_start;
+            }
+            ("re", &[x, y, w, h]) => {
+                self.path.extend([
+                    (x, y, x + w, y),
+                    (x + w, y, x + w, y + h),
+                    (x + w, y + h, x, y + h),
+                    (x, y + h, x, y),
+                ]);
+                self.current_point = (x, y);
+                self.subpath_start = (x, y);
+            }
+            ("S" | "s" | "f" | "F" | "f*" | "B" | "B*" | "b" | "b*", _) => self.paint_path(),
+            ("n", _) => self.path.clear(),
+            ("BT", _) => {
+                self.text_matrix = Matrix::IDENTITY;
+                self.line_matrix = Matrix::IDENTITY;
+            }
+            ("Tf", _) => {
+                self.encoding = operation
+                    .operands
+                    .first()
+                    .and_then(|name| name.as_name().ok())
+                    .and_then(|name| self.encodings.get(name));
+            }
+            ("TL", &[leading]) => self.leading = leading,
+            ("Td", &[x, y]) => self.move_text(x, y),
+            ("TD", &[x, y]) => {
+                self.leading = -y;
+                self.move_text(x, y);
+            }
+            ("Tm", &[a, b, c, d, e, f]) => {
+                self.text_matrix = Matrix([a, b, c, d, e, f]);
+                self.line_matrix = self.text_matrix;
+            }
+            ("T*", _) => self.move_text(0.0, -self.leading),
+            ("Tj", _) => self.show_text(&operation.operands),
+            ("'" | "\"", _) => {
+                self.move_text(0.0, -self.leading);
+                self.show_text(&operation.operands[operation.operands.len().saturating_sub(1)..]);
+            }
+            ("TJ", _) => {
+                if let Some(Object::Array(items)) = operation.operands.first() {
+                    self.show_text(items);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn move_text(&mut self, x: f32, y: f32) {
+        self.line_matrix = Matrix::translation(x, y).then(&self.line_matrix);
+        self.text_matrix = self.line_matrix;
+    }
+
+    /// Records the text of the given strings. Large negative adjustments between strings of a
+    /// `TJ` array, which are commonly used instead of spaces, are rendered as spaces.
+    fn show_text(&mut self, items: &[Object]) {
+        let mut text = String::new();
+        for item in items {
+            match item {
+                Object::String(bytes, _) => text.push_str(&self.decode(bytes)),
+                Object::Integer(_) | Object::Real(_)
+                    if item.as_float().unwrap_or_default() < -200.0 =>
+                {
+                    text.push(' ')
+                }
+                _ => {}
+            }
+        }
+        let (x, y) = self.text_matrix.then(&self.ctm).apply(0.0, 0.0);
+        self.chunks.push(TextChunk { x, y, text });
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        self.encoding
+            .and_then(|encoding| Document::decode_text(encoding, bytes).ok())
+            .unwrap_or_else(|| bytes.iter().map(|&byte| byte as char).collect())
+    }
+
+    /// Classifies the segments of the current path into horizontal and vertical lines.
+    fn paint_path(&mut self) {
+        for (x1, y1, x2, y2) in self.path.drain(..) {
+            let (x1, y1) = self.ctm.apply(x1, y1);
+            let (x2, y2) = self.ctm.apply(x2, y2);
+            if (y1 - y2).abs() < TOLERANCE && (x1 - x2).abs() >= TOLERANCE {
+                self.horizontal
+                    .push(((y1 + y2) / 2.0, x1.min(x2), x1.max(x2)));
+            } else if (x1 - x2).abs() < TOLERANCE && (y1 - y2).abs() >= TOLERANCE {
+                self.vertical
+                    .push(((x1 + x2) / 2.0, y1.min(y2), y1.max(y2)));
+            }
+        }
+    }
+
+    /// Assigns the text of the page to the cells of the grid formed by the ruling lines.
+    fn table(mut self) -> Vec<Vec<String>> {
+        let mut rows = cluster(self.horizontal.iter().map(|line| line.0).collect());
+        rows.reverse();
+        let columns = cluster(self.vertical.iter().map(|line| line.0).collect());
+        if rows.len() < 2 || columns.len() < 2 {
+            return Vec::new();
+        }
+        self.chunks
+            .sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+        let mut cells = vec![vec![Vec::new(); columns.len() - 1]; rows.len() - 1];
+        for chunk in &self.chunks {
+            let row = rows
+                .windows(2)
+                .position(|r| r[0] > chunk.y && chunk.y >= r[1]);
+            let column = columns
+                .windows(2)
+                .position(|c| c[0] <= chunk.x && chunk.x < c[1]);
+            if let (Some(row), Some(column)) = (row, column) {
+                cells[row][column].push(chunk.text.trim());
+            }
+        }
+        cells
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|cell| cell.join(" "))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|row| row.iter().any(|cell| !cell.is_empty()))
+            .collect()
+    }
+}
+
+/// Sorts the given coordinates and merges coordinates closer than the tolerance.
+fn cluster(mut coordinates: Vec<f32>) -> Vec<f32> {
+    coordinates.sort_by(f32::total_cmp);
+    let mut clusters: Vec<f32> = Vec::new();
+    for coordinate in coordinates {
+        match clusters.last() {
+            Some(last) if coordinate - last < TOLERANCE => {}
+            _ => clusters.push(coordinate),
+        }
+    }
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_pdf_table() {
+        let data = std::fs::read("tests/table_test.pdf").unwrap();
+        let rows = read_pdf_table(&data).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["Name", "Age", "Country"],
+                vec!["John", "30", "USA"],
+                vec!["Alice", "25", "United Kingdom"],
+                vec!["Bob", "40", "Canada"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cluster() {
+        assert_eq!(
+            cluster(vec![10.0, 700.0, 10.5, 50.0]),
+            vec![10.0, 50.0, 700.0]
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{FileError, FileFormat, FileReader};
+
+/// A set of default options for the files of a common ecosystem, selected with
+/// [`FileReader::profile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    /// CSV files exported by spreadsheet applications with a non-English locale: byte order
+    /// marks are detected, fields are separated by `;` and numbers use `,` as decimal
+    /// separator.
+    Excel,
+    /// Tabular output of bioinformatics tools: lines starting with `#` are comments and `NA`
+    /// denotes missing values. Identifiers like `chr2` and `chr10` are sorted naturally, see
+    /// [`Profile::natural_sort`].
+    Bioinformatics,
+}
+
+impl Profile {
+    /// Returns whether values of the ecosystem are sorted naturally, i.e. with embedded
+    /// numbers compared by their numeric value, see [`FileReader::natural_sort`].
+    pub fn natural_sort(&self) -> bool {
+        matches!(self, Profile::Bioinformatics)
+    }
+}
+
+impl FileReader {
+    /// Applies the options of the given profile. Options set before are overridden, options
+    /// set afterwards take precedence. The `;` delimiter of [`Profile::Excel`] replaces the
+    /// delimiter of CSV files.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{FileReader, Profile};
+    ///
+    /// let mut reader = FileReader::new("tests/excel_test.csv", Some(','))
+    ///     .expect("Failed to create FileReader")
+    ///     .profile(Profile::Excel)
+    ///     .expect("Failed to apply profile");
+    /// assert_eq!(reader.headers().unwrap(), vec!["Name", "Amount"]);
+    /// ```
+    pub fn profile(self, profile: Profile) -> Result<FileReader, FileError> {
+        let reader = match profile {
+            Profile::Excel => {
+                let mut reader = self.detect_bom(true)?.decimal_separator(',');
+                if let FileFormat::Csv(_) = reader.file_format {
+                    reader.file_format = FileFormat::Csv(';');
+                }
+                reader
+            }
+            Profile::Bioinformatics => self.comment(Some('#')).null_values(&["NA"]),
+        };
+        Ok(reader.natural_sort(profile.natural_sort()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Order;
+
+    #[test]
+    fn test_excel_profile() {
+        let mut reader = FileReader::new("tests/excel_test.csv", Some(','))
+            .expect("Failed to create FileReader")
+            .profile(Profile::Excel)
+            .unwrap();
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(
+            records,
+            vec![vec!["Müller", "1234.5"], vec!["Schmidt", "-0.75"]]
+        );
+    }
+
+    #[test]
+    fn test_bioinformatics_profile() {
+        let mut reader = FileReader::new("tests/variants.tsv", Some('\t'))
+            .expect("Failed to create FileReader")
+            .profile(Profile::Bioinformatics)
+            .unwrap();
+        assert_eq!(reader.headers().unwrap(), vec!["chrom", "pos", "qual"]);
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(
+            records,
+            vec![vec!["chr2", "100", "30.5"], vec!["chr10", "200", ""]]
+        );
+        assert!(Profile::Bioinformatics.natural_sort());
+        assert!(!Profile::Excel.natural_sort());
+        let records: Vec<Vec<String>> = reader
+            .records_sorted_by("chrom", Order::Ascending)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records[0][0], "chr2");
+    }
+}
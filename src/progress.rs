@@ -0,0 +1,134 @@
+use crate::{FileError, FileReader};
+use std::io::{Seek, SeekFrom};
+use std::sync::atomic::Ordering;
+
+/// The number of records between two progress reports.
+const PROGRESS_INTERVAL: u64 = 1000;
+
+/// The progress of reading the records of a file, see [`FileReader::records_with_progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    bytes_read: u64,
+    total_bytes: Option<u64>,
+    records: u64,
+}
+
+impl Progress {
+    /// Returns the number of bytes that have been read from the file. For compressed files,
+    /// these are the decompressed bytes.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// Returns the size of the file in bytes, if it is known. The size of compressed files is
+    /// unknown.
+    pub fn total_bytes(&self) -> Option<u64> {
+        self.total_bytes
+    }
+
+    /// Returns the number of records that have been returned.
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Returns the read fraction of the file between 0 and 1, if its size is known.
+    pub fn fraction(&self) -> Option<f64> {
+        self.total_bytes.map(|total_bytes| match total_bytes {
+            0 => 1.0,
+            total_bytes => (self.bytes_read as f64 / total_bytes as f64).min(1.0),
+        })
+    }
+}
+
+impl FileReader {
+    /// Returns an iterator over the records of the file like [`FileReader::records`] that
+    /// reports its progress to the given callback after every 1000 records and once more
+    /// when all records have been returned. Formats that are read completely before the
+    /// first record is returned, like JSON arrays, report all bytes as read immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let records: Vec<Vec<String>> = reader
+    ///     .records_with_progress(|progress| {
+    ///         println!("{} records, {:?} of the file", progress.records(), progress.fraction());
+    ///     })
+    ///     .unwrap()
+    ///     .collect();
+    /// ```
+    pub fn records_with_progress<'a, F>(
+        &'a mut self,
+        mut callback: F,
+    ) -> Result<impl Iterator<Item = Vec<String>> + 'a, FileError>
+    where
+        F: FnMut(Progress) + 'a,
+    {
+        let total_bytes = self.file.get_mut().input_len()?;
+        self.file.seek(SeekFrom::Start(0))?;
+        let input_position = self.file.get_ref().input_position();
+        let mut records = self.records()?;
+        let mut count = 0;
+        let mut finished = false;
+        Ok(std::iter::from_fn(move || {
+            let record = records.next();
+            match record {
+                Some(_) => count += 1,
+                None if finished => return None,
+                None => finished = true,
+            }
+            if finished || count % PROGRESS_INTERVAL == 0 {
+                callback(Progress {
+                    bytes_read: input_position.load(Ordering::Relaxed),
+                    total_bytes,
+                    records: count,
+                });
+            }
+            record
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_with_progress() {
+        let mut reports = Vec::new();
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        let records: Vec<Vec<String>> = reader
+            .records_with_progress(|progress| reports.push(progress))
+            .unwrap()
+            .collect();
+        assert_eq!(records.len(), 3);
+        let size = std::fs::metadata("tests/test.csv").unwrap().len();
+        assert_eq!(
+            reports,
+            vec![Progress {
+                bytes_read: size,
+                total_bytes: Some(size),
+                records: 3,
+            }]
+        );
+        assert_eq!(reports[0].fraction(), Some(1.0));
+    }
+
+    #[test]
+    fn test_progress_of_compressed_file() {
+        let mut reports = Vec::new();
+        let mut reader =
+            FileReader::new("tests/test.csv.gz", Some(',')).expect("Failed to create FileReader");
+        let count = reader
+            .records_with_progress(|progress| reports.push(progress))
+            .unwrap()
+            .count();
+        assert_eq!(count, 3);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].total_bytes(), None);
+        assert!(reports[0].bytes_read() > 0);
+    }
+}
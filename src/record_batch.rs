@@ -0,0 +1,215 @@
+use crate::types::timestamp_micros;
+use crate::{ColumnType, FileError, FileReader};
+use arrow::array::{ArrayRef, StringArray, TimestampMicrosecondArray};
+use arrow::compute::{cast_with_options, CastOptions};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use std::sync::Arc;
+
+impl FileReader {
+    /// Returns an iterator over the records of the file as Arrow record batches with at most
+    /// `batch_size` rows each. The type of each column is inferred from all of its values as
+    /// by [`FileReader::infer_types`], which takes an additional pass over the file. Integers,
+    /// floats, booleans, dates and timestamps are stored as `Int64`, `Float64`, `Boolean`,
+    /// `Date32` and `Timestamp(Microsecond)` in UTC, where empty values, `NA` and the
+    /// placeholder set by [`FileReader::null_output`] are null. Other columns are stored as
+    /// `Utf8` with their values unchanged.
+    /// Requires the `arrow` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let batches = reader
+    ///     .to_record_batches(1024)
+    ///     .unwrap()
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(batches[0].num_rows(), 3);
+    /// ```
+    pub fn to_record_batches(
+        &mut self,
+        batch_size: usize,
+    ) -> Result<impl Iterator<Item = Result<RecordBatch, FileError>> + '_, FileError> {
+        let headers = self.headers()?;
+        let types = self.infer_types(usize::MAX)?;
+        let schema: SchemaRef = Arc::new(Schema::new(
+            headers
+                .iter()
+                .zip(&types)
+                .map(|(header, column_type)| Field::new(header, data_type(*column_type), true))
+                .collect::<Vec<_>>(),
+        ));
+        let null = self.normalization.null_output().to_string();
+        let mut records = self.records()?;
+        Ok(std::iter::from_fn(move || {
+            let batch: Vec<Vec<String>> = records.by_ref().take(batch_size.max(1)).collect();
+            (!batch.is_empty()).then(|| record_batch(&schema, &batch, &null))
+        }))
+    }
+}
+
+fn data_type(column_type: ColumnType) -> DataType {
+    match column_type {
+        ColumnType::Integer => DataType::Int64,
+        ColumnType::Float => DataType::Float64,
+        ColumnType::Bool => DataType::Boolean,
+        ColumnType::Date => DataType::Date32,
+        ColumnType::DateTime => DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+        ColumnType::String => DataType::Utf8,
+    }
+}
+
+/// Converts records to a record batch with the given schema, where `null` denotes missing
+/// values in addition to empty values and `NA`. Timestamps are parsed as by [`FileReader::infer_types`], since Arrow rejects some
+/// of the accepted formats.
+fn record_batch(
+    schema: &SchemaRef,
+    records: &[Vec<String>],
+    null: &str,
+) -> Result<RecordBatch, FileError> {
+    let options = CastOptions {
+        safe: false,
+        ..CastOptions::default()
+    };
+    let columns = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let values = records.iter().map(|record| record.get(index));
+            if field.data_type() == &DataType::Utf8 {
+                return Ok(Arc::new(StringArray::from_iter(values)) as ArrayRef);
+            }
+            let values = values.map(|value| {
+                value
+                    .map(|value| value.trim())
+                    .filter(|value| !value.is_empty() && *value != "NA" && *value != null)
+            });
+            if let DataType::Timestamp(_, timezone) = field.data_type() {
+                let timestamps = values
+                    .map(|value| {
+                        value
+                            .map(|value| {
+                                timestamp_micros(value).ok_or_else(|| {
+                                    ArrowError::CastError(format!(
+                                        "Cannot cast string '{}' to value of {}",
+                                        value,
+                                        field.data_type()
+                                    ))
+                                })
+                            })
+                            .transpose()
+                    })
+                    .collect::<Result<TimestampMicrosecondArray, _>>()?;
+                return Ok(Arc::new(timestamps.with_timezone_opt(timezone.clone())) as ArrayRef);
+            }
+            let values = StringArray::from_iter(values);
+            cast_with_options(&values, field.data_type(), &options)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(RecordBatch::try_new(Arc::clone(schema), columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, BooleanArray, Date32Array, Float64Array, Int64Array};
+
+    #[test]
+    fn test_record_batches() {
+        let mut reader =
+            FileReader::new("tests/types.csv", Some(',')).expect("Failed to create FileReader");
+        let batches = reader
+            .to_record_batches(2)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].num_rows(), 2);
+        assert_eq!(batches[1].num_rows(), 1);
+        let batch = &batches[0];
+        let types: Vec<&DataType> = batch
+            .schema_ref()
+            .fields()
+            .iter()
+            .map(|field| field.data_type())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                &DataType::Int64,
+                &DataType::Float64,
+                &DataType::Boolean,
+                &DataType::Date32,
+                &DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+                &DataType::Utf8,
+            ]
+        );
+        let ids = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(ids.values(), &[1, 2]);
+        let scores = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(scores.value(0), 0.5);
+        assert!(scores.is_null(1));
+        let valid = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .unwrap();
+        assert!(valid.value(0) && !valid.value(1));
+        let days = batch
+            .column(3)
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .unwrap();
+        assert_eq!(days.value(0), 19753);
+        let times = batches[1]
+            .column(4)
+            .as_any()
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .unwrap();
+        assert_eq!(times.value(0), 1_709_247_600_000_000);
+        let labels = batches[1]
+            .column(5)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(labels.value(0), "NA");
+    }
+
+    #[test]
+    fn test_record_batches_timestamps() {
+        let schema: SchemaRef = Arc::new(Schema::new(vec![Field::new(
+            "time",
+            data_type(ColumnType::DateTime),
+            true,
+        )]));
+        let records = [
+            vec!["2024-01-31T12:00".to_string()],
+            vec!["2016-12-31T23:59:60Z".to_string()],
+            vec!["2024-01-31".to_string()],
+            vec![".".to_string()],
+        ];
+        let batch = record_batch(&schema, &records, ".").unwrap();
+        let times = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .unwrap();
+        assert_eq!(times.value(0), 1_706_702_400_000_000);
+        assert_eq!(times.value(1), 1_483_228_800_000_000);
+        assert_eq!(times.value(2), 1_706_659_200_000_000);
+        assert!(times.is_null(3));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::FileError;
+use std::io::Read;
+
+/// Resolves the given URL to an HTTP(S) URL that can be fetched directly.
+/// `s3://bucket/key` URLs are mapped to the virtual-hosted endpoint of the bucket.
+pub(crate) fn resolve_url(url: &str) -> Result<String, FileError> {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        Ok(url.to_string())
+    } else if let Some(location) = url.strip_prefix("s3://") {
+        match location.split_once('/') {
+            Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => {
+                Ok(format!("https://{}.s3.amazonaws.com/{}", bucket, key))
+            }
+            _ => Err(FileError::UnsupportedUrl(url.to_string())),
+        }
+    } else {
+        Err(FileError::UnsupportedUrl(url.to_string()))
+    }
+}
+
+/// Returns the path component of the given URL without query string or fragment.
+pub(crate) fn url_path(url: &str) -> &str {
+    let url = url.split(['?', '#']).next().unwrap_or(url);
+    match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("", |index| &rest[index..]),
+        None => url,
+    }
+}
+
+/// Downloads the body of the given URL.
+pub(crate) fn fetch(url: &str) -> Result<Vec<u8>, FileError> {
+    let response = ureq::get(url).call().map_err(Box::new)?;
+    let mut body = Vec::new();
+    response.into_reader().read_to_end(&mut body)?;
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_url() {
+        assert_eq!(
+            resolve_url("https://example.com/data.csv").unwrap(),
+            "https://example.com/data.csv"
+        );
+        assert_eq!(
+            resolve_url("s3://bucket/results/data.json").unwrap(),
+            "https://bucket.s3.amazonaws.com/results/data.json"
+        );
+        assert_eq!(
+            resolve_url("s3://bucket").err().unwrap(),
+            FileError::UnsupportedUrl("s3://bucket".to_string())
+        );
+        assert_eq!(
+            resolve_url("ftp://example.com/data.csv").err().unwrap(),
+            FileError::UnsupportedUrl("ftp://example.com/data.csv".to_string())
+        );
+    }
+
+    #[test]
+    fn test_url_path() {
+        assert_eq!(url_path("https://example.com/data.csv"), "/data.csv");
+        assert_eq!(
+            url_path("https://example.com/dir/data.tsv?version=2#top"),
+            "/dir/data.tsv"
+        );
+        assert_eq!(url_path("https://example.com"), "");
+    }
+}
@@ -0,0 +1,251 @@
+use crate::FileReader;
+use serde_json::{Map, Value};
+
+/// How arrays in JSON and YAML files are rendered into cells, see [`FileReader::json_arrays`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ArrayRendering {
+    /// Arrays are serialized as JSON, e.g. `["dog","cat"]`.
+    #[default]
+    Json,
+    /// The elements of arrays are joined with the given separator, e.g. `dog;cat`.
+    Join(String),
+    /// Each element of an array is put into its own record, repeating the other values of the
+    /// record. Several arrays of a record are exploded in parallel, so the n-th records hold
+    /// the n-th elements of all arrays, with missing values where an array is shorter.
+    /// Records with empty arrays are kept with a missing value.
+    Explode,
+}
+
+/// How the values of JSON and YAML files are rendered into cells, see
+/// [`FileReader::json_arrays`] and [`FileReader::json_max_depth`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct JsonRendering {
+    arrays: ArrayRendering,
+    max_depth: Option<usize>,
+}
+
+/// The rendered value of a cell, with all elements of an array to be exploded.
+enum Cell {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl JsonRendering {
+    /// Returns whether a single item may be rendered into several records.
+    pub(crate) fn explodes(&self) -> bool {
+        self.arrays == ArrayRendering::Explode
+    }
+
+    /// Renders an item into records, which is a single record unless arrays are exploded.
+    /// Null values are replaced with the given placeholder.
+    pub(crate) fn render(&self, value: Value, null: &str) -> Vec<Vec<String>> {
+        let mut cells = Vec::new();
+        match value {
+            Value::Object(object) => self.render_object(object, 1, null, &mut cells),
+            value => cells.push(self.render_value(value, null)),
+        }
+        let len = cells
+            .iter()
+            .map(|cell| match cell {
+                Cell::One(_) => 1,
+                Cell::Many(values) => values.len(),
+            })
+            .max()
+            .unwrap_or(1);
+        (0..len)
+            .map(|row| {
+                cells
+                    .iter()
+                    .map(|cell| match cell {
+                        Cell::One(value) => value.clone(),
+                        Cell::Many(values) => {
+                            values.get(row).cloned().unwrap_or_else(|| null.to_string())
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Adds the headers of the columns an object is rendered into that are not yet known.
+    pub(crate) fn headers(&self, headers: &mut Vec<String>, object: &Map<String, Value>) {
+        self.object_headers(headers, object, "", 1);
+    }
+
+    fn object_headers(
+        &self,
+        headers: &mut Vec<String>,
+        object: &Map<String, Value>,
+        prefix: &str,
+        depth: usize,
+    ) {
+        for (key, value) in object {
+            let header = if prefix.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                Value::Object(inner) if self.expands(depth) => {
+                    self.object_headers(headers, inner, &header, depth + 1)
+                }
+                _ => {
+                    if !headers.contains(&header) {
+                        headers.push(header);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns whether objects nested at the given depth are expanded into columns.
+    fn expands(&self, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max_depth| depth <= max_depth)
+    }
+
+    fn render_object(
+        &self,
+        object: Map<String, Value>,
+        depth: usize,
+        null: &str,
+        cells: &mut Vec<Cell>,
+    ) {
+        for (_, value) in object {
+            match value {
+                Value::Object(inner) if self.expands(depth) => {
+                    self.render_object(inner, depth + 1, null, cells)
+                }
+                value => cells.push(self.render_value(value, null)),
+            }
+        }
+    }
+
+    fn render_value(&self, value: Value, null: &str) -> Cell {
+        match (value, &self.arrays) {
+            (Value::Array(values), ArrayRendering::Join(separator)) => Cell::One(
+                values
+                    .into_iter()
+                    .map(|value| scalar(value, null))
+                    .collect::<Vec<_>>()
+                    .join(separator),
+            ),
+            (Value::Array(values), ArrayRendering::Explode) if values.is_empty() => {
+                Cell::One(null.to_string())
+            }
+            (Value::Array(values), ArrayRendering::Explode) => Cell::Many(
+                values
+                    .into_iter()
+                    .map(|value| scalar(value, null))
+                    .collect(),
+            ),
+            (value, _) => Cell::One(scalar(value, null)),
+        }
+    }
+}
+
+/// Renders a value as text, with strings unquoted and arrays and objects as JSON.
+fn scalar(value: Value, null: &str) -> String {
+    match value {
+        Value::String(s) => s,
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => null.to_string(),
+        value => value.to_string(),
+    }
+}
+
+impl FileReader {
+    /// Sets how arrays in JSON and YAML files are rendered into cells. Defaults to
+    /// [`ArrayRendering::Json`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{ArrayRendering, FileReader};
+    ///
+    /// let mut reader = FileReader::new("tests/inner_array_test.json", None)
+    ///     .expect("Failed to create FileReader")
+    ///     .json_arrays(ArrayRendering::Join(";".to_string()));
+    /// let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+    /// assert_eq!(records[0], vec!["30", "USA", "John", "dog;cat"]);
+    /// ```
+    pub fn json_arrays(mut self, arrays: ArrayRendering) -> FileReader {
+        self.json_rendering.arrays = arrays;
+        self
+    }
+
+    /// Sets the number of levels of nested objects in JSON and YAML files that are expanded
+    /// into columns named by their path, like `bank.institution`. Objects nested deeper are
+    /// kept as a single column containing the object as JSON. By default, all levels are
+    /// expanded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/nested_test.json", None)
+    ///     .expect("Failed to create FileReader")
+    ///     .json_max_depth(0);
+    /// assert!(reader.headers().unwrap().contains(&"bank".to_string()));
+    /// ```
+    pub fn json_max_depth(mut self, max_depth: usize) -> FileReader {
+        self.json_rendering.max_depth = Some(max_depth);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_explode_arrays() {
+        let rendering = JsonRendering {
+            arrays: ArrayRendering::Explode,
+            max_depth: None,
+        };
+        let record = json!({"a": [1, 2], "b": "x", "c": ["y", "z"], "d": []});
+        assert_eq!(
+            rendering.render(record, "NA"),
+            vec![vec!["1", "x", "y", "NA"], vec!["2", "x", "z", "NA"]]
+        );
+        let record = json!({"a": [1, 2, 3], "b": "x", "c": ["y"]});
+        assert_eq!(
+            rendering.render(record, "NA"),
+            vec![
+                vec!["1", "x", "y"],
+                vec!["2", "x", "NA"],
+                vec!["3", "x", "NA"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let rendering = JsonRendering {
+            arrays: ArrayRendering::Json,
+            max_depth: Some(1),
+        };
+        let record = json!({"a": {"b": {"c": 1}, "d": 2}, "e": [null]});
+        let mut headers = Vec::new();
+        rendering.headers(&mut headers, record.as_object().unwrap());
+        assert_eq!(headers, vec!["a.b", "a.d", "e"]);
+        assert_eq!(
+            rendering.render(record, ""),
+            vec![vec![r#"{"c":1}"#, "2", "[null]"]]
+        );
+    }
+
+    #[test]
+    fn test_explode_json_records() {
+        let mut reader = FileReader::new("tests/inner_array_test.json", None)
+            .expect("Failed to create FileReader")
+            .json_arrays(ArrayRendering::Explode);
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[1], vec!["30", "USA", "John", "cat"]);
+        assert_eq!(records[3], vec!["40", "Canada", "Bob", ""]);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{FileError, FileReader};
+
+impl FileReader {
+    /// Returns an iterator over at most `limit` records of the file, starting after the first
+    /// `offset` records. The offset counts records as they appear in the file, including
+    /// malformed ones. Skipped CSV records are not decoded, other formats skip them while
+    /// reading.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let records: Vec<Vec<String>> = reader.records_limit(1, 1).unwrap().collect();
+    /// assert_eq!(records, vec![vec!["Alice", "25", "UK"]]);
+    /// ```
+    pub fn records_limit(
+        &mut self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<impl Iterator<Item = Vec<String>> + '_, FileError> {
+        Ok(self
+            .try_records_from(offset)?
+            .map_while(Result::ok)
+            .take(limit))
+    }
+
+    /// Returns a uniform random sample of `n` records of the file in file order.
+    /// The sample is drawn by reservoir sampling in a single pass and is reproducible for
+    /// the same seed. Files with at most `n` records are returned completely.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let sample = reader.sample_records(2, 42).unwrap();
+    /// assert_eq!(sample.len(), 2);
+    /// ```
+    pub fn sample_records(&mut self, n: usize, seed: u64) -> Result<Vec<Vec<String>>, FileError> {
+        let mut rng = SplitMix64(seed);
+        let mut reservoir: Vec<(usize, Vec<String>)> = Vec::with_capacity(n);
+        for (index, record) in self.records()?.enumerate() {
+            if reservoir.len() < n {
+                reservoir.push((index, record));
+            } else {
+                let slot = (rng.next() % (index as u64 + 1)) as usize;
+                if slot < n {
+                    reservoir[slot] = (index, record);
+                }
+            }
+        }
+        reservoir.sort_by_key(|(index, _)| *index);
+        Ok(reservoir.into_iter().map(|(_, record)| record).collect())
+    }
+}
+
+/// A small, fast pseudo-random number generator, which suffices for sampling.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorPolicy;
+
+    #[test]
+    fn test_records_limit() {
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        let records: Vec<Vec<String>> = reader.records_limit(0, 2).unwrap().collect();
+        assert_eq!(
+            records,
+            vec![vec!["John", "30", "USA"], vec!["Alice", "25", "UK"]]
+        );
+        assert_eq!(reader.records_limit(3, 2).unwrap().count(), 0);
+        let mut reader =
+            FileReader::new("tests/test.json", None).expect("Failed to create FileReader");
+        let records: Vec<Vec<String>> = reader.records_limit(2, 5).unwrap().collect();
+        assert_eq!(records, vec![vec!["40", "Canada", "Bob"]]);
+    }
+
+    #[test]
+    fn test_records_limit_counts_malformed_records() {
+        let mut reader = FileReader::new("tests/malformed_test.csv", Some(','))
+            .expect("Failed to create FileReader")
+            .error_policy(ErrorPolicy::Collect);
+        let all: Vec<Vec<String>> = reader.records().unwrap().collect();
+        let records: Vec<Vec<String>> = reader.records_limit(2, 10).unwrap().collect();
+        assert_eq!(records, all[1..]);
+        assert!(reader.rejected_rows().is_empty());
+    }
+
+    #[test]
+    fn test_sample_records() {
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        let sample = reader.sample_records(2, 7).unwrap();
+        assert_eq!(sample.len(), 2);
+        assert_eq!(sample, reader.sample_records(2, 7).unwrap());
+        let all: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert!(sample.iter().all(|record| all.contains(record)));
+        assert_eq!(reader.sample_records(10, 7).unwrap(), all);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{ColumnType, FileError, FileReader};
+use serde::{Deserialize, Serialize};
+
+/// The columns of a file together with their inferred types, see [`FileReader::schema`].
+/// Schemas can be serialized to compare them with later versions of the file by
+/// [`FileReader::compare_schema`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schema {
+    columns: Vec<SchemaColumn>,
+}
+
+impl Schema {
+    /// Returns the columns of the schema.
+    pub fn columns(&self) -> &[SchemaColumn] {
+        &self.columns
+    }
+
+    fn column_type(&self, name: &str) -> Option<ColumnType> {
+        self.columns
+            .iter()
+            .find(|column| column.name == name)
+            .map(|column| column.column_type)
+    }
+}
+
+/// A column of a [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaColumn {
+    name: String,
+    column_type: ColumnType,
+}
+
+impl SchemaColumn {
+    /// Returns the header of the column.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the inferred type of the column.
+    pub fn column_type(&self) -> ColumnType {
+        self.column_type
+    }
+}
+
+/// The differences between two schemas, see [`FileReader::compare_schema`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaChanges {
+    /// The columns that are new in the current file.
+    pub added: Vec<String>,
+    /// The columns of the previous schema that are missing in the current file.
+    pub removed: Vec<String>,
+    /// The columns whose type has changed.
+    pub retyped: Vec<RetypedColumn>,
+}
+
+impl SchemaChanges {
+    /// Returns whether the schemas are the same apart from the order of their columns.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.retyped.is_empty()
+    }
+}
+
+/// A column whose type differs between two schemas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetypedColumn {
+    pub name: String,
+    pub previous: ColumnType,
+    pub current: ColumnType,
+}
+
+impl FileReader {
+    /// Returns the schema of the file with the type of each column inferred from all of its
+    /// values as by [`FileReader::infer_types`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{ColumnType, FileReader};
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let schema = reader.schema().unwrap();
+    /// assert_eq!(schema.columns()[1].name(), "Age");
+    /// assert_eq!(schema.columns()[1].column_type(), ColumnType::Integer);
+    /// ```
+    pub fn schema(&mut self) -> Result<Schema, FileError> {
+        let headers = self.headers()?;
+        let types = self.infer_types(usize::MAX)?;
+        Ok(Schema {
+            columns: headers
+                .into_iter()
+                .zip(types)
+                .map(|(name, column_type)| SchemaColumn { name, column_type })
+                .collect(),
+        })
+    }
+
+    /// Compares the schema of the file with a schema of a previous version of the file,
+    /// e.g. one deserialized from a prior run. Columns are matched by their headers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{FileReader, Schema};
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let previous: Schema = serde_json::from_str(
+    ///     r#"{"columns": [{"name": "Name", "column_type": "String"}, {"name": "City", "column_type": "String"}]}"#,
+    /// ).unwrap();
+    /// let changes = reader.compare_schema(&previous).unwrap();
+    /// assert_eq!(changes.added, vec!["Age", "Country"]);
+    /// assert_eq!(changes.removed, vec!["City"]);
+    /// ```
+    pub fn compare_schema(&mut self, previous: &Schema) -> Result<SchemaChanges, FileError> {
+        let current = self.schema()?;
+        let mut changes = SchemaChanges::default();
+        for column in &current.columns {
+            match previous.column_type(&column.name) {
+                None => changes.added.push(column.name.clone()),
+                Some(previous) if previous != column.column_type => {
+                    changes.retyped.push(RetypedColumn {
+                        name: column.name.clone(),
+                        previous,
+                        current: column.column_type,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        changes.removed = previous
+            .columns
+            .iter()
+            .filter(|column| current.column_type(&column.name).is_none())
+            .map(|column| column.name.clone())
+            .collect();
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_roundtrip() {
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        let schema = reader.schema().unwrap();
+        let serialized = serde_json::to_string(&schema).unwrap();
+        assert!(serialized.contains(r#"{"name":"Age","column_type":"Integer"}"#));
+        let previous: Schema = serde_json::from_str(&serialized).unwrap();
+        assert!(reader.compare_schema(&previous).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_retyped_column() {
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        let mut previous = reader.schema().unwrap();
+        previous.columns[1].column_type = ColumnType::Float;
+        assert_eq!(
+            reader.compare_schema(&previous).unwrap(),
+            SchemaChanges {
+                added: vec![],
+                removed: vec![],
+                retyped: vec![RetypedColumn {
+                    name: "Age".to_string(),
+                    previous: ColumnType::Float,
+                    current: ColumnType::Integer,
+                }],
+            }
+        );
+    }
+}
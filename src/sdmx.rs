@@ -0,0 +1,140 @@
+use crate::FileError;
+use roxmltree::{Document, Node};
+
+/// Header of the observation dimension in generic data messages that do not name it.
+const TIME_PERIOD: &str = "TIME_PERIOD";
+/// Header of the observation value in generic data messages.
+const OBS_VALUE: &str = "OBS_VALUE";
+
+/// Extracts the observations of an SDMX-ML data message as a table.
+/// Both generic and structure-specific messages are supported. Every observation becomes a
+/// row containing the dimensions and attributes of its series followed by its own dimensions,
+/// measures and attributes. The first row contains the headers in order of first appearance.
+pub(crate) fn read_sdmx_table(text: &str) -> Result<Vec<Vec<String>>, FileError> {
+    let document = Document::parse(text)?;
+    let mut observations = Vec::new();
+    for dataset in document
+        .descendants()
+        .filter(|node| node.tag_name().name() == "DataSet")
+    {
+        for child in dataset.children().filter(Node::is_element) {
+            match child.tag_name().name() {
+                "Series" => {
+                    let series = fields(child);
+                    for obs in child
+                        .children()
+                        .filter(|node| node.tag_name().name() == "Obs")
+                    {
+                        observations.push([series.clone(), fields(obs)].concat());
+                    }
+                }
+                "Obs" => observations.push(fields(child)),
+                _ => {}
+            }
+        }
+    }
+    let mut headers: Vec<String> = Vec::new();
+    for (id, _) in observations.iter().flatten() {
+        if !headers.contains(id) {
+            headers.push(id.clone());
+        }
+    }
+    let records = observations.into_iter().map(|observation| {
+        headers
+            .iter()
+            .map(|header| {
+                observation
+                    .iter()
+                    .find(|(id, _)| id == header)
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or_default()
+            })
+            .collect()
+    });
+    Ok(std::iter::once(headers.clone()).chain(records).collect())
+}
+
+/// Returns the components of a series or observation as pairs of id and value.
+/// Structure-specific messages store them as plain attributes, generic messages as
+/// `Value`, `ObsDimension` and `ObsValue` child elements.
+fn fields(node: Node) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = node
+        .attributes()
+        .filter(|attribute| attribute.namespace().is_none())
+        .map(|attribute| (attribute.name().to_string(), attribute.value().to_string()))
+        .collect();
+    for child in node.children().filter(Node::is_element) {
+        match child.tag_name().name() {
+            "SeriesKey" | "ObsKey" | "Attributes" => fields.extend(
+                child
+                    .children()
+                    .filter(|node| node.tag_name().name() == "Value")
+                    .filter_map(|value| {
+                        Some((value.attribute("id")?.to_string(), value_of(value)))
+                    }),
+            ),
+            "ObsDimension" => fields.push((
+                child.attribute("id").unwrap_or(TIME_PERIOD).to_string(),
+                value_of(child),
+            )),
+            "ObsValue" => fields.push((
+                child.attribute("id").unwrap_or(OBS_VALUE).to_string(),
+                value_of(child),
+            )),
+            _ => {}
+        }
+    }
+    fields
+}
+
+fn value_of(node: Node) -> String {
+    node.attribute("value").unwrap_or_default().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_generic_sdmx() {
+        let text = std::fs::read_to_string("tests/sdmx_test.xml").unwrap();
+        let rows = read_sdmx_table(&text).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    "FREQ",
+                    "GEO",
+                    "UNIT",
+                    "TIME_PERIOD",
+                    "OBS_VALUE",
+                    "OBS_STATUS"
+                ],
+                vec!["A", "DE", "PC", "2021", "3.2", ""],
+                vec!["A", "DE", "PC", "2022", "8.7", "p"],
+                vec!["A", "FR", "PC", "2021", "2.1", ""],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_structure_specific_sdmx() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<message:StructureSpecificData xmlns:message="http://www.sdmx.org/resources/sdmxml/schemas/v2_1/message" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <message:DataSet xsi:type="ns1:DataSetType">
+    <Series FREQ="A" GEO="DE">
+      <Obs TIME_PERIOD="2021" OBS_VALUE="3.2"/>
+      <Obs TIME_PERIOD="2022" OBS_VALUE="8.7" OBS_STATUS="p"/>
+    </Series>
+  </message:DataSet>
+</message:StructureSpecificData>"#;
+        assert_eq!(
+            read_sdmx_table(text).unwrap(),
+            vec![
+                vec!["FREQ", "GEO", "TIME_PERIOD", "OBS_VALUE", "OBS_STATUS"],
+                vec!["A", "DE", "2021", "3.2", ""],
+                vec!["A", "DE", "2022", "8.7", "p"],
+            ]
+        );
+    }
+}
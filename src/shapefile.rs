@@ -0,0 +1,333 @@
+use crate::encoding::encoding_for_label;
+use crate::wkt::{Geometry, Position};
+use crate::FileError;
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+use std::fs;
+use std::path::Path;
+
+/// Header of the column containing the geometry of a feature.
+const GEOMETRY: &str = "geometry";
+
+/// Reads the attribute table of a shapefile from the `.dbf` file next to the given `.shp`
+/// file, with the geometries of the `.shp` file as WKT in a trailing `geometry` column.
+/// The encoding of the attributes is taken from the `.cpg` file if present, otherwise UTF-8
+/// is assumed with a fallback to Windows-1252. The first row contains the headers.
+pub(crate) fn read_shapefile_table(path: &Path) -> Result<Vec<Vec<String>>, FileError> {
+    let encoding = fs::read_to_string(path.with_extension("cpg"))
+        .ok()
+        .and_then(|label| encoding_for_label(label.trim()));
+    let (mut headers, records) = read_dbf(&fs::read(path.with_extension("dbf"))?, encoding)?;
+    let geometries = read_shp(&fs::read(path)?)?;
+    if records.len() != geometries.len() {
+        return Err(invalid_shapefile(
+            "different numbers of dBase records and shapes",
+        ));
+    }
+    headers.push(GEOMETRY.to_string());
+    let records = records
+        .into_iter()
+        .zip(geometries)
+        .filter_map(|(record, geometry)| {
+            let mut record = record?;
+            record.push(geometry.map(|geometry| geometry.wkt()).unwrap_or_default());
+            Some(record)
+        });
+    Ok(std::iter::once(headers).chain(records).collect())
+}
+
+/// The field names and the values of the records of a dBase file, where records marked as
+/// deleted are `None`.
+type DbfTable = (Vec<String>, Vec<Option<Vec<String>>>);
+
+/// Parses a dBase file into its field names and the values of its records.
+fn read_dbf(data: &[u8], encoding: Option<&'static Encoding>) -> Result<DbfTable, FileError> {
+    if data.len() < 32 {
+        return Err(invalid_shapefile("truncated dBase header"));
+    }
+    let records = u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize;
+    let header_length = u16::from_le_bytes(data[8..10].try_into().unwrap()) as usize;
+    let record_length = u16::from_le_bytes(data[10..12].try_into().unwrap()) as usize;
+    let decode = |bytes: &[u8]| -> String {
+        let text = match encoding {
+            Some(encoding) => encoding.decode_without_bom_handling(bytes).0,
+            None => match UTF_8.decode_without_bom_handling_and_without_replacement(bytes) {
+                Some(text) => text,
+                None => WINDOWS_1252.decode_without_bom_handling(bytes).0,
+            },
+        };
+        text.trim_matches(|c: char| c.is_whitespace() || c == '\0')
+            .to_string()
+    };
+    let mut fields = Vec::new();
+    for descriptor in data
+        .get(32..header_length)
+        .ok_or_else(|| invalid_shapefile("truncated dBase header"))?
+        .chunks_exact(32)
+        .take_while(|descriptor| descriptor[0] != 0x0d)
+    {
+        fields.push((
+            decode(&descriptor[..11]),
+            descriptor[11],
+            descriptor[16] as usize,
+        ));
+    }
+    if record_length == 0 {
+        return Err(invalid_shapefile("invalid dBase record length"));
+    }
+    let body = &data[header_length..];
+    if records
+        .checked_mul(record_length)
+        .is_none_or(|length| length > body.len())
+    {
+        return Err(invalid_shapefile("truncated dBase records"));
+    }
+    let mut rows = Vec::with_capacity(records);
+    for record in body.chunks_exact(record_length).take(records) {
+        if record[0] == b'*' {
+            rows.push(None);
+            continue;
+        }
+        let mut offset = 1;
+        let mut row = Vec::with_capacity(fields.len());
+        for (_, kind, length) in &fields {
+            let value = decode(record.get(offset..offset + length).unwrap_or_default());
+            row.push(match (kind, value.as_str()) {
+                (b'L', "T" | "t" | "Y" | "y") => "true".to_string(),
+                (b'L', "F" | "f" | "N" | "n") => "false".to_string(),
+                (b'L', _) => String::new(),
+                _ => value,
+            });
+            offset += length;
+        }
+        rows.push(Some(row));
+    }
+    Ok((fields.into_iter().map(|(name, _, _)| name).collect(), rows))
+}
+
+/// Parses the geometries of the records of a `.shp` file. Null shapes are returned as `None`.
+fn read_shp(data: &[u8]) -> Result<Vec<Option<Geometry>>, FileError> {
+    let mut geometries = Vec::new();
+    let mut offset = 100;
+    while offset + 8 <= data.len() {
+        let length = usize::try_from(i32::from_be_bytes(
+            data[offset + 4..offset + 8].try_into().unwrap(),
+        ))
+        .map_err(|_| invalid_shapefile("negative shape record length"))?
+            * 2;
+        let content = data
+            .get(offset + 8..offset + 8 + length)
+            .ok_or_else(|| invalid_shapefile("truncated shape record"))?;
+        geometries.push(read_shape(content)?);
+        offset += 8 + length;
+    }
+    Ok(geometries)
+}
+
+fn read_shape(content: &[u8]) -> Result<Option<Geometry>, FileError> {
+    let mut reader = ShapeReader { content, offset: 4 };
+    Ok(match reader.int_at(0)? {
+        0 => None,
+        1 | 11 | 21 => Some(Geometry::Point(Some(reader.position()?))),
+        8 | 18 | 28 => {
+            reader.offset += 32;
+            let count = reader.count()?;
+            Some(Geometry::MultiPoint(
+                (0..count)
+                    .map(|_| reader.position())
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
+        kind @ (3 | 13 | 23 | 5 | 15 | 25) => {
+            reader.offset += 32;
+            let parts = reader.count()?;
+            let count = reader.count()?;
+            let mut starts = (0..parts)
+                .map(|_| reader.count())
+                .collect::<Result<Vec<_>, FileError>>()?;
+            starts.push(count);
+            let points = (0..count)
+                .map(|_| reader.position())
+                .collect::<Result<Vec<_>, _>>()?;
+            let parts: Vec<Vec<Position>> = starts
+                .windows(2)
+                .map(|part| points.get(part[0]..part[1]).unwrap_or_default().to_vec())
+                .collect();
+            Some(if kind % 10 == 3 {
+                match <[_; 1]>::try_from(parts) {
+                    Ok([line]) => Geometry::LineString(line),
+                    Err(parts) => Geometry::MultiLineString(parts),
+                }
+            } else {
+                polygons(parts)
+            })
+        }
+        kind => {
+            return Err(invalid_shapefile(&format!(
+                "unsupported shape type {}",
+                kind
+            )))
+        }
+    })
+}
+
+/// Groups the rings of a shapefile polygon into polygons. Outer rings are oriented clockwise,
+/// holes counterclockwise and follow the outer ring they belong to.
+fn polygons(rings: Vec<Vec<Position>>) -> Geometry {
+    let mut polygons: Vec<Vec<Vec<Position>>> = Vec::new();
+    for ring in rings {
+        match polygons.last_mut() {
+            Some(polygon) if signed_area(&ring) > 0.0 => polygon.push(ring),
+            _ => polygons.push(vec![ring]),
+        }
+    }
+    match <[_; 1]>::try_from(polygons) {
+        Ok([polygon]) => Geometry::Polygon(polygon),
+        Err(polygons) => Geometry::MultiPolygon(polygons),
+    }
+}
+
+/// Returns the signed area of a ring, which is positive for counterclockwise rings.
+fn signed_area(ring: &[Position]) -> f64 {
+    ring.windows(2)
+        .map(|edge| edge[0].0 * edge[1].1 - edge[1].0 * edge[0].1)
+        .sum::<f64>()
+        / 2.0
+}
+
+struct ShapeReader<'a> {
+    content: &'a [u8],
+    offset: usize,
+}
+
+impl ShapeReader<'_> {
+    fn bytes<const N: usize>(&self, offset: usize) -> Result<[u8; N], FileError> {
+        Ok(self
+            .content
+            .get(offset..offset + N)
+            .ok_or_else(|| invalid_shapefile("truncated shape record"))?
+            .try_into()
+            .unwrap())
+    }
+
+    fn int_at(&self, offset: usize) -> Result<i32, FileError> {
+        Ok(i32::from_le_bytes(self.bytes(offset)?))
+    }
+
+    fn int(&mut self) -> Result<i32, FileError> {
+        let value = self.int_at(self.offset)?;
+        self.offset += 4;
+        Ok(value)
+    }
+
+    /// Reads a number of parts or points, which must not be negative.
+    fn count(&mut self) -> Result<usize, FileError> {
+        usize::try_from(self.int()?).map_err(|_| invalid_shapefile("negative count"))
+    }
+
+    fn position(&mut self) -> Result<Position, FileError> {
+        let x = f64::from_le_bytes(self.bytes(self.offset)?);
+        let y = f64::from_le_bytes(self.bytes(self.offset + 8)?);
+        self.offset += 16;
+        Ok((x, y))
+    }
+}
+
+fn invalid_shapefile(message: &str) -> FileError {
+    FileError::InvalidGeometry(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_shapefile_table() {
+        let rows = read_shapefile_table(Path::new("tests/parks.shp")).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["NAME", "AREA", "OPEN", "geometry"],
+                vec![
+                    "Stadtpark",
+                    "96.00",
+                    "true",
+                    "POLYGON ((0 0, 0 10, 10 10, 10 0, 0 0), (2 2, 4 2, 4 4, 2 4, 2 2))"
+                ],
+                vec![
+                    "Dreieck",
+                    "25.00",
+                    "false",
+                    "POLYGON ((20 0, 25 5, 30 0, 20 0))"
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deleted_record() {
+        let rows = read_shapefile_table(Path::new("tests/parks_deleted.shp")).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[1],
+            vec![
+                "Dreieck",
+                "25.00",
+                "false",
+                "POLYGON ((20 0, 25 5, 30 0, 20 0))"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_record_count_mismatch() {
+        let directory = std::env::temp_dir().join("readervzrd_shapefile_count_test");
+        fs::create_dir_all(&directory).unwrap();
+        fs::copy("tests/parks.shp", directory.join("parks.shp")).unwrap();
+        let mut dbf = fs::read("tests/parks.dbf").unwrap();
+        dbf[4] = 1;
+        fs::write(directory.join("parks.dbf"), dbf).unwrap();
+        assert!(matches!(
+            read_shapefile_table(&directory.join("parks.shp")),
+            Err(FileError::InvalidGeometry(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_lengths() {
+        let mut dbf = vec![0; 33];
+        dbf[8] = 33;
+        dbf[32] = 0x0d;
+        assert!(matches!(
+            read_dbf(&dbf, None),
+            Err(FileError::InvalidGeometry(_))
+        ));
+        dbf[4] = 2;
+        dbf[10] = 1;
+        dbf.push(b' ');
+        assert!(matches!(
+            read_dbf(&dbf, None),
+            Err(FileError::InvalidGeometry(_))
+        ));
+        dbf.push(b' ');
+        assert_eq!(read_dbf(&dbf, None).unwrap().1.len(), 2);
+        let mut shp = vec![0; 108];
+        shp[104..108].copy_from_slice(&(-2i32).to_be_bytes());
+        assert!(matches!(read_shp(&shp), Err(FileError::InvalidGeometry(_))));
+        let mut multipoint = 8i32.to_le_bytes().to_vec();
+        multipoint.extend([0; 32]);
+        multipoint.extend((-1i32).to_le_bytes());
+        assert!(matches!(
+            read_shape(&multipoint),
+            Err(FileError::InvalidGeometry(_))
+        ));
+    }
+
+    #[test]
+    fn test_polygons() {
+        let square = |x: f64| vec![(x, 0.0), (x, 1.0), (x + 1.0, 1.0), (x + 1.0, 0.0), (x, 0.0)];
+        assert_eq!(
+            polygons(vec![square(0.0), square(5.0)]),
+            Geometry::MultiPolygon(vec![vec![square(0.0)], vec![square(5.0)]])
+        );
+    }
+}
@@ -0,0 +1,501 @@
+use crate::{ColumnType, FileError, FileReader};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, Seek, SeekFrom};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::Arc;
+
+/// The number of records sorted in memory before they are spilled to a temporary file.
+const RUN_SIZE: usize = 100_000;
+
+/// The number of records the type of the sort column is inferred from.
+const TYPE_SAMPLE_SIZE: usize = 1000;
+
+/// The direction records are sorted in by [`FileReader::records_sorted_by`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Order {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl FileReader {
+    /// Returns the records of the file ordered by the given column. Columns inferred as
+    /// [`ColumnType::Integer`] or [`ColumnType::Float`] are compared numerically, other columns
+    /// by their text, naturally if set by [`FileReader::natural_sort`]. Empty values, `NA` and
+    /// the placeholder set by [`FileReader::null_output`] come last in both orders and records
+    /// with equal values keep their order in the file. Files with many records are sorted in
+    /// runs that are spilled to temporary files and merged while iterating. Iteration stops
+    /// after an error reading a temporary file back, which is returned as the last item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{FileReader, Order};
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let records: Vec<Vec<String>> = reader
+    ///     .records_sorted_by("Age", Order::Descending)
+    ///     .unwrap()
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(records[0], vec!["Bob", "40", "Canada"]);
+    /// ```
+    pub fn records_sorted_by(
+        &mut self,
+        column: &str,
+        order: Order,
+    ) -> Result<impl Iterator<Item = Result<Vec<String>, FileError>>, FileError> {
+        self.records_sorted_in_runs(column, order, RUN_SIZE)
+    }
+
+    /// Sets whether text is sorted naturally by [`FileReader::records_sorted_by`], i.e. with
+    /// embedded numbers compared by their numeric value, so that `chr2` comes before `chr10`.
+    /// Enabled by [`Profile::Bioinformatics`](crate::Profile::Bioinformatics).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{FileReader, Order};
+    ///
+    /// let mut reader = FileReader::new("tests/variants.tsv", Some('\t'))
+    ///     .expect("Failed to create FileReader")
+    ///     .comment(Some('#'))
+    ///     .natural_sort(true);
+    /// let records: Vec<Vec<String>> = reader
+    ///     .records_sorted_by("chrom", Order::Ascending)
+    ///     .unwrap()
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(records[0][0], "chr2");
+    /// ```
+    pub fn natural_sort(mut self, natural_sort: bool) -> FileReader {
+        self.natural_sort = natural_sort;
+        self
+    }
+
+    fn records_sorted_in_runs(
+        &mut self,
+        column: &str,
+        order: Order,
+        run_size: usize,
+    ) -> Result<SortedRecords, FileError> {
+        let index = self
+            .headers()?
+            .iter()
+            .position(|header| header == column)
+            .ok_or_else(|| FileError::UnknownColumn(column.to_string()))?;
+        let numeric = matches!(
+            self.infer_types(TYPE_SAMPLE_SIZE)?.get(index),
+            Some(ColumnType::Integer | ColumnType::Float)
+        );
+        let key = Key {
+            index,
+            numeric,
+            natural: self.natural_sort,
+            order,
+            null: self.normalization.null_output().into(),
+        };
+        let mut spilled = Vec::new();
+        let mut run = Vec::new();
+        for record in self.records()? {
+            run.push(record);
+            if run.len() == run_size {
+                spilled.push(SpillFile::write(key.sort(std::mem::take(&mut run)))?);
+            }
+        }
+        let mut runs: Vec<Run> = spilled
+            .into_iter()
+            .map(Run::spilled)
+            .collect::<Result<_, _>>()?;
+        runs.push(Run::Memory(key.sort(run).into_iter()));
+        SortedRecords::new(key, runs)
+    }
+}
+
+/// How records are compared while sorting.
+#[derive(Debug, Clone)]
+struct Key {
+    index: usize,
+    numeric: bool,
+    natural: bool,
+    order: Order,
+    /// The placeholder of missing values.
+    null: Arc<str>,
+}
+
+impl Key {
+    fn sort(&self, mut records: Vec<Vec<String>>) -> Vec<Vec<String>> {
+        records.sort_by(|a, b| self.compare(a, b));
+        records
+    }
+
+    /// Returns the value records are sorted by, or `None` if it is missing.
+    fn value<'a>(&self, record: &'a [String]) -> Option<&'a str> {
+        record
+            .get(self.index)
+            .filter(|value| **value != *self.null)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty() && *value != "NA")
+    }
+
+    fn compare(&self, a: &[String], b: &[String]) -> Ordering {
+        match (self.value(a), self.value(b)) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => {
+                let ordering = if self.numeric {
+                    compare_numeric(a, b)
+                } else if self.natural {
+                    compare_natural(a, b)
+                } else {
+                    a.cmp(b)
+                };
+                match self.order {
+                    Order::Ascending => ordering,
+                    Order::Descending => ordering.reverse(),
+                }
+            }
+        }
+    }
+}
+
+/// Compares numbers by their value, with values that are no numbers after all numbers.
+fn compare_numeric(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+/// Compares text with runs of digits compared by their numeric value. Values that only
+/// differ in leading zeros are compared by their text.
+fn compare_natural(a: &str, b: &str) -> Ordering {
+    let (mut rest_a, mut rest_b) = (a, b);
+    loop {
+        match (rest_a.chars().next(), rest_b.chars().next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (number_a, next_a) = split_digits(rest_a);
+                let (number_b, next_b) = split_digits(rest_b);
+                let (number_a, number_b) = (
+                    number_a.trim_start_matches('0'),
+                    number_b.trim_start_matches('0'),
+                );
+                let ordering = number_a
+                    .len()
+                    .cmp(&number_b.len())
+                    .then_with(|| number_a.cmp(number_b));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                (rest_a, rest_b) = (next_a, next_b);
+            }
+            (Some(x), Some(y)) if x != y => return x.cmp(&y),
+            (Some(x), Some(_)) => {
+                rest_a = &rest_a[x.len_utf8()..];
+                rest_b = &rest_b[x.len_utf8()..];
+            }
+        }
+    }
+}
+
+/// Splits text into its leading digits and the rest.
+fn split_digits(text: &str) -> (&str, &str) {
+    text.split_at(
+        text.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len()),
+    )
+}
+
+/// A temporary file holding a sorted run of records, which is removed when dropped.
+struct SpillFile {
+    path: PathBuf,
+    file: File,
+}
+
+impl SpillFile {
+    fn write(records: Vec<Vec<String>>) -> Result<SpillFile, FileError> {
+        let spill = SpillFile::create()?;
+        let mut writer = csv::WriterBuilder::new()
+            .flexible(true)
+            .from_writer(&spill.file);
+        for record in records {
+            writer.write_record(record)?;
+        }
+        writer.flush()?;
+        drop(writer);
+        Ok(spill)
+    }
+
+    /// Creates a new temporary file that is only accessible by the current user. Names taken
+    /// by existing files are skipped, so that no other file is written to or read from.
+    fn create() -> Result<SpillFile, FileError> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        loop {
+            let path = std::env::temp_dir().join(format!(
+                "readervzrd-sort-{}-{}.csv",
+                std::process::id(),
+                COUNTER.fetch_add(1, AtomicOrdering::Relaxed)
+            ));
+            let mut options = OpenOptions::new();
+            options.read(true).write(true).create_new(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+            match options.open(&path) {
+                Ok(file) => return Ok(SpillFile { path, file }),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// A sorted run of records.
+enum Run {
+    Memory(std::vec::IntoIter<Vec<String>>),
+    Spilled {
+        records: csv::StringRecordsIntoIter<BufReader<File>>,
+        /// Keeps the temporary file until the run has been merged.
+        _file: SpillFile,
+    },
+}
+
+impl Run {
+    fn spilled(spill: SpillFile) -> Result<Run, FileError> {
+        let mut file = spill.file.try_clone()?;
+        file.seek(SeekFrom::Start(0))?;
+        let reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(BufReader::new(file));
+        Ok(Run::Spilled {
+            records: reader.into_records(),
+            _file: spill,
+        })
+    }
+
+    fn next(&mut self) -> Result<Option<Vec<String>>, FileError> {
+        match self {
+            Run::Memory(records) => Ok(records.next()),
+            Run::Spilled { records, .. } => Ok(records
+                .next()
+                .transpose()?
+                .map(|record| record.iter().map(String::from).collect())),
+        }
+    }
+}
+
+/// The next record of a run, ordered for a min-heap.
+struct Head {
+    record: Vec<String>,
+    run: usize,
+    key: Key,
+}
+
+impl Ord for Head {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key
+            .compare(&self.record, &other.record)
+            .then(self.run.cmp(&other.run))
+            .reverse()
+    }
+}
+
+impl PartialOrd for Head {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Head {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Head {}
+
+/// An iterator merging sorted runs of records. Runs are numbered in file order, so records
+/// with equal values are yielded in file order.
+struct SortedRecords {
+    key: Key,
+    runs: Vec<Run>,
+    heads: BinaryHeap<Head>,
+    /// An error reading a run, which is returned after the current record.
+    error: Option<FileError>,
+}
+
+impl SortedRecords {
+    fn new(key: Key, mut runs: Vec<Run>) -> Result<SortedRecords, FileError> {
+        let mut heads = BinaryHeap::new();
+        for (run, records) in runs.iter_mut().enumerate() {
+            if let Some(record) = records.next()? {
+                heads.push(Head {
+                    record,
+                    run,
+                    key: key.clone(),
+                });
+            }
+        }
+        Ok(SortedRecords {
+            key,
+            runs,
+            heads,
+            error: None,
+        })
+    }
+}
+
+impl Iterator for SortedRecords {
+    type Item = Result<Vec<String>, FileError>;
+
+    fn next(&mut self) -> Option<Result<Vec<String>, FileError>> {
+        if let Some(err) = self.error.take() {
+            self.heads.clear();
+            return Some(Err(err));
+        }
+        let Head { record, run, .. } = self.heads.pop()?;
+        match self.runs[run].next() {
+            Ok(Some(next)) => self.heads.push(Head {
+                record: next,
+                run,
+                key: self.key.clone(),
+            }),
+            Ok(None) => {}
+            Err(err) => self.error = Some(err),
+        }
+        Some(Ok(record))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_sort_numeric_column() {
+        let mut reader = FileReader::new("tests/variants.tsv", Some('\t'))
+            .expect("Failed to create FileReader")
+            .comment(Some('#'));
+        let records: Vec<Vec<String>> = reader
+            .records_sorted_by("qual", Order::Descending)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records[0], vec!["chr2", "100", "30.5"]);
+        assert_eq!(records[1], vec!["chr10", "200", "NA"]);
+        let records: Vec<Vec<String>> = reader
+            .records_sorted_by("pos", Order::Descending)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records[0][0], "chr10");
+    }
+
+    #[test]
+    fn test_external_merge_sort() {
+        let mut reader =
+            FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+        let names = |reader: &mut FileReader, column, order| -> Vec<String> {
+            reader
+                .records_sorted_in_runs(column, order, 1)
+                .unwrap()
+                .map(|record| record.unwrap()[0].clone())
+                .collect()
+        };
+        assert_eq!(
+            names(&mut reader, "Age", Order::Ascending),
+            vec!["Alice", "John", "Bob"]
+        );
+        assert_eq!(
+            names(&mut reader, "Country", Order::Descending),
+            vec!["John", "Alice", "Bob"]
+        );
+        assert_eq!(
+            reader.records_sorted_by("Missing", Order::Ascending).err(),
+            Some(FileError::UnknownColumn("Missing".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_spill_read_error() {
+        let spill = SpillFile::write(vec![vec!["a".to_string()]]).unwrap();
+        (&spill.file).write_all(b"\xff\n").unwrap();
+        let key = Key {
+            index: 0,
+            numeric: false,
+            natural: false,
+            order: Order::Ascending,
+            null: "".into(),
+        };
+        let mut records = SortedRecords::new(key, vec![Run::spilled(spill).unwrap()]).unwrap();
+        assert_eq!(records.next().unwrap().unwrap(), vec!["a"]);
+        assert!(records.next().unwrap().is_err());
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn test_sort_null_placeholder() {
+        let path = std::env::temp_dir().join("readervzrd_sort_null_test.csv");
+        std::fs::write(&path, "id,score\n1,\n2,10\n3,5\n").unwrap();
+        let mut reader = FileReader::new(path.to_str().unwrap(), Some(','))
+            .expect("Failed to create FileReader")
+            .null_values(&[""])
+            .null_output(".");
+        let records: Vec<Vec<String>> = reader
+            .records_sorted_by("score", Order::Descending)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            records,
+            vec![vec!["2", "10"], vec!["3", "5"], vec!["1", "."]]
+        );
+    }
+
+    #[test]
+    fn test_compare_natural() {
+        let mut values = vec!["chr10", "chrX", "chr2", "chr02", "chr1a", "chr1", ""];
+        values.sort_by(|a, b| compare_natural(a, b));
+        assert_eq!(
+            values,
+            vec!["", "chr1", "chr1a", "chr02", "chr2", "chr10", "chrX"]
+        );
+    }
+
+    #[test]
+    fn test_compare_numeric() {
+        let key = Key {
+            index: 0,
+            numeric: true,
+            natural: false,
+            order: Order::Ascending,
+            null: "".into(),
+        };
+        let records = ["10", "", "9", "abc", "-1.5"]
+            .iter()
+            .map(|value| vec![value.to_string()])
+            .collect();
+        assert_eq!(
+            key.sort(records),
+            vec![vec!["-1.5"], vec!["9"], vec!["10"], vec!["abc"], vec![""]]
+        );
+    }
+}
@@ -0,0 +1,232 @@
+#[cfg(feature = "gpkg")]
+use crate::gpkg;
+use crate::{FileError, FileReader};
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags, Statement};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+
+/// The number of rows read ahead of the records that have been consumed.
+const ROW_BUFFER: usize = 1024;
+
+/// A SQLite database together with the query whose result is read as table.
+#[derive(Debug, Clone)]
+pub(crate) struct Database {
+    path: PathBuf,
+    query: Option<String>,
+}
+
+impl Database {
+    pub(crate) fn new(path: &str) -> Database {
+        Database {
+            path: PathBuf::from(path),
+            query: None,
+        }
+    }
+
+    /// Returns the column names of the query result without running the query.
+    pub(crate) fn headers(&self) -> Result<Vec<String>, FileError> {
+        let connection = self.connect()?;
+        let statement = self.prepare(&connection)?;
+        Ok(statement
+            .column_names()
+            .into_iter()
+            .map(String::from)
+            .collect())
+    }
+
+    /// Runs the query and returns its rows together with their row numbers, where the header
+    /// row is row 1. NULL values are replaced with the given placeholder. The rows borrow the
+    /// connection, so they are read on a separate thread while the records are consumed.
+    pub(crate) fn rows(
+        &self,
+        null: &str,
+    ) -> Result<impl Iterator<Item = (u64, Result<Vec<String>, FileError>)>, FileError> {
+        // Prepare the query once up front, so that invalid queries fail right away.
+        self.headers()?;
+        let (sender, receiver) = mpsc::sync_channel(ROW_BUFFER);
+        let database = self.clone();
+        let null = null.to_string();
+        thread::spawn(move || {
+            let result = (|| -> Result<(), FileError> {
+                let connection = database.connect()?;
+                let mut statement = database.prepare(&connection)?;
+                let geometries = geometry_columns(&connection, &statement)?;
+                let mut rows = statement.query([])?;
+                while let Some(row) = rows.next()? {
+                    let record = geometries
+                        .iter()
+                        .enumerate()
+                        .map(|(index, geometry)| {
+                            Ok(match (row.get_ref(index)?, geometry) {
+                                (ValueRef::Null, _) => null.clone(),
+                                #[cfg(feature = "gpkg")]
+                                (ValueRef::Blob(blob), true) => gpkg::read_gpkg_geometry(blob)?,
+                                (value, _) => value_text(value),
+                            })
+                        })
+                        .collect::<Result<_, FileError>>()?;
+                    if sender.send(Ok(record)).is_err() {
+                        break;
+                    }
+                }
+                Ok(())
+            })();
+            if let Err(err) = result {
+                let _ = sender.send(Err(err));
+            }
+        });
+        Ok((2..).zip(receiver))
+    }
+
+    fn connect(&self) -> Result<Connection, FileError> {
+        Ok(Connection::open_with_flags(
+            &self.path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )?)
+    }
+
+    /// Prepares the query, which defaults to reading the first table in alphabetical order,
+    /// or the first feature table of GeoPackages.
+    fn prepare<'a>(&self, connection: &'a Connection) -> Result<Statement<'a>, FileError> {
+        let query = match &self.query {
+            Some(query) => query.clone(),
+            #[cfg(feature = "gpkg")]
+            None if gpkg::is_geopackage(connection)? => {
+                table_query(&gpkg::feature_table(connection)?)
+            }
+            None => table_query(&connection.query_row(
+                "SELECT name FROM sqlite_master WHERE type = 'table' \
+                 AND name NOT LIKE 'sqlite_%' ORDER BY name LIMIT 1",
+                [],
+                |row| row.get::<_, String>(0),
+            )?),
+        };
+        Ok(connection.prepare(&query)?)
+    }
+}
+
+impl FileReader {
+    /// Sets the table of a SQLite database that is read. Defaults to the first table in
+    /// alphabetical order. Has no effect on other file formats. Requires the `sqlite` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/results.db", None)
+    ///     .expect("Failed to create FileReader")
+    ///     .table("samples");
+    /// assert_eq!(reader.headers().unwrap(), vec!["name", "tissue"]);
+    /// ```
+    pub fn table(self, table: &str) -> FileReader {
+        self.query(&table_query(table))
+    }
+
+    /// Sets a query whose result is read from a SQLite database instead of a table.
+    /// Has no effect on other file formats. Requires the `sqlite` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/results.db", None)
+    ///     .expect("Failed to create FileReader")
+    ///     .query("SELECT sample, score FROM results WHERE score > 0.5");
+    /// let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+    /// assert_eq!(records, vec![vec!["A", "0.9"]]);
+    /// ```
+    pub fn query(mut self, query: &str) -> FileReader {
+        if let Some(database) = self.database.as_mut() {
+            database.query = Some(query.to_string());
+        }
+        self
+    }
+}
+
+/// Returns for each column of a statement whether it holds the geometries of a GeoPackage,
+/// which are rendered as WKT.
+fn geometry_columns(
+    connection: &Connection,
+    statement: &Statement,
+) -> Result<Vec<bool>, FileError> {
+    #[cfg(feature = "gpkg")]
+    if gpkg::is_geopackage(connection)? {
+        let geometries = gpkg::geometry_columns(connection)?;
+        return Ok(statement
+            .column_names()
+            .into_iter()
+            .map(|name| geometries.iter().any(|geometry| geometry == name))
+            .collect());
+    }
+    #[cfg(not(feature = "gpkg"))]
+    let _ = connection;
+    Ok(vec![false; statement.column_count()])
+}
+
+fn table_query(table: &str) -> String {
+    format!("SELECT * FROM \"{}\"", table.replace('"', "\"\""))
+}
+
+/// Renders a SQLite value as text, with NULL as empty string and blobs in hexadecimal.
+pub(crate) fn value_text(value: ValueRef) -> String {
+    match value {
+        ValueRef::Null => String::new(),
+        ValueRef::Integer(i) => i.to_string(),
+        ValueRef::Real(f) => f.to_string(),
+        ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned(),
+        ValueRef::Blob(blob) => blob.iter().map(|byte| format!("{:02x}", byte)).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorPolicy;
+
+    #[test]
+    fn test_read_default_table() {
+        let mut reader =
+            FileReader::new("tests/results.db", None).expect("Failed to create FileReader");
+        assert_eq!(
+            reader.headers().unwrap(),
+            vec!["sample", "score", "count", "data"]
+        );
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(
+            records,
+            vec![vec!["A", "0.9", "3", "cafe"], vec!["B", "0.25", "", ""]]
+        );
+    }
+
+    #[test]
+    fn test_headers_without_running_query() {
+        let mut reader = FileReader::new("tests/results.db", None)
+            .expect("Failed to create FileReader")
+            .query("SELECT sample, abs(-9223372036854775808) AS overflow FROM results")
+            .error_policy(ErrorPolicy::Strict);
+        assert_eq!(reader.headers().unwrap(), vec!["sample", "overflow"]);
+        assert!(matches!(reader.try_records().unwrap().next(), Some(Err(_))));
+    }
+
+    #[test]
+    fn test_compressed_database() {
+        assert_eq!(
+            FileReader::new("tests/results.db.gz", None).err(),
+            Some(FileError::CompressedDatabase(
+                "tests/results.db.gz".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_invalid_query() {
+        let mut reader = FileReader::new("tests/results.db", None)
+            .expect("Failed to create FileReader")
+            .table("missing");
+        assert!(matches!(reader.headers(), Err(FileError::SqliteError(_))));
+    }
+}
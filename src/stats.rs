@@ -0,0 +1,202 @@
+use crate::{FileError, FileReader};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
+
+/// The number of smallest value hashes kept to estimate the number of distinct values.
+/// Columns with fewer distinct values are counted exactly.
+const DISTINCT_SKETCH_SIZE: usize = 4096;
+
+/// Summary statistics of the values of a column as computed by [`FileReader::column_stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    /// The smallest value, compared numerically if all values are numbers.
+    pub min: Option<String>,
+    /// The largest value, compared numerically if all values are numbers.
+    pub max: Option<String>,
+    /// The number of empty and `NA` values and of placeholders set by
+    /// [`FileReader::null_output`].
+    pub null_count: u64,
+    /// The number of distinct values besides nulls. Exact for up to 4096 distinct values,
+    /// estimated otherwise.
+    pub distinct_count: u64,
+    /// The mean of the values if all values are numbers.
+    pub mean: Option<f64>,
+}
+
+impl FileReader {
+    /// Computes summary statistics for each column in a single pass over all records.
+    /// Empty values, `NA` and the placeholder set by [`FileReader::null_output`] are counted as
+    /// nulls and otherwise ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileReader;
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let stats = reader.column_stats().unwrap();
+    /// assert_eq!(stats[1].min.as_deref(), Some("25"));
+    /// assert_eq!(stats[1].max.as_deref(), Some("40"));
+    /// assert_eq!(stats[0].distinct_count, 3);
+    /// ```
+    pub fn column_stats(&mut self) -> Result<Vec<ColumnStats>, FileError> {
+        let mut accumulators = vec![Accumulator::default(); self.headers()?.len()];
+        let null = self.normalization.null_output().to_string();
+        for record in self.records()? {
+            for (accumulator, value) in accumulators.iter_mut().zip(&record) {
+                accumulator.update(value, &null);
+            }
+        }
+        Ok(accumulators.into_iter().map(Accumulator::finish).collect())
+    }
+}
+
+/// The running statistics of a single column.
+#[derive(Debug, Clone, Default)]
+struct Accumulator {
+    null_count: u64,
+    count: u64,
+    /// Whether all values seen so far are numbers.
+    non_numeric: bool,
+    sum: f64,
+    min_number: Option<(f64, String)>,
+    max_number: Option<(f64, String)>,
+    min_text: Option<String>,
+    max_text: Option<String>,
+    hashes: BTreeSet<u64>,
+}
+
+impl Accumulator {
+    fn update(&mut self, value: &str, null: &str) {
+        let trimmed = value.trim();
+        if trimmed.is_empty() || trimmed == "NA" || value == null {
+            self.null_count += 1;
+            return;
+        }
+        self.count += 1;
+        match trimmed.parse::<f64>() {
+            Ok(number) if number.is_finite() => {
+                self.sum += number;
+                if self
+                    .min_number
+                    .as_ref()
+                    .is_none_or(|(min, _)| number < *min)
+                {
+                    self.min_number = Some((number, value.to_string()));
+                }
+                if self
+                    .max_number
+                    .as_ref()
+                    .is_none_or(|(max, _)| number > *max)
+                {
+                    self.max_number = Some((number, value.to_string()));
+                }
+            }
+            _ => self.non_numeric = true,
+        }
+        if self.min_text.as_deref().is_none_or(|min| value < min) {
+            self.min_text = Some(value.to_string());
+        }
+        if self.max_text.as_deref().is_none_or(|max| value > max) {
+            self.max_text = Some(value.to_string());
+        }
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.hashes.len() < DISTINCT_SKETCH_SIZE {
+            self.hashes.insert(hash);
+        } else if hash < *self.hashes.last().unwrap() && self.hashes.insert(hash) {
+            self.hashes.pop_last();
+        }
+    }
+
+    fn finish(self) -> ColumnStats {
+        let numeric = self.count > 0 && !self.non_numeric;
+        let (min, max) = if numeric {
+            (
+                self.min_number.map(|(_, value)| value),
+                self.max_number.map(|(_, value)| value),
+            )
+        } else {
+            (self.min_text, self.max_text)
+        };
+        ColumnStats {
+            min,
+            max,
+            null_count: self.null_count,
+            distinct_count: estimate_distinct(&self.hashes),
+            mean: numeric.then(|| self.sum / self.count as f64),
+        }
+    }
+}
+
+/// Estimates the number of distinct values from the smallest hashes of a column (the
+/// k-minimum-values sketch). If the sketch is not full, it contains all distinct hashes.
+fn estimate_distinct(hashes: &BTreeSet<u64>) -> u64 {
+    match hashes.last() {
+        Some(&largest) if hashes.len() == DISTINCT_SKETCH_SIZE => {
+            let fraction = (largest as f64 + 1.0) / (u64::MAX as f64 + 1.0);
+            ((DISTINCT_SKETCH_SIZE - 1) as f64 / fraction).round() as u64
+        }
+        _ => hashes.len() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(values: &[&str]) -> ColumnStats {
+        let mut accumulator = Accumulator::default();
+        for value in values {
+            accumulator.update(value, "");
+        }
+        accumulator.finish()
+    }
+
+    #[test]
+    fn test_null_placeholder_stats() {
+        let path = std::env::temp_dir().join("readervzrd_stats_null_test.csv");
+        std::fs::write(&path, "id,score\n1,10\n2,\n3,30\n").unwrap();
+        let mut reader = FileReader::new(path.to_str().unwrap(), Some(','))
+            .expect("Failed to create FileReader")
+            .null_values(&[""])
+            .null_output(".");
+        let stats = reader.column_stats().unwrap();
+        assert_eq!(stats[1].null_count, 1);
+        assert_eq!(stats[1].min.as_deref(), Some("10"));
+        assert_eq!(stats[1].max.as_deref(), Some("30"));
+        assert_eq!(stats[1].mean, Some(20.0));
+    }
+
+    #[test]
+    fn test_numeric_column_stats() {
+        let stats = stats(&["10", "9.5", "", "NA", "100", "10"]);
+        assert_eq!(stats.min.as_deref(), Some("9.5"));
+        assert_eq!(stats.max.as_deref(), Some("100"));
+        assert_eq!(stats.null_count, 2);
+        assert_eq!(stats.distinct_count, 3);
+        assert_eq!(stats.mean, Some(32.375));
+    }
+
+    #[test]
+    fn test_text_column_stats() {
+        let text = stats(&["10", "b", "a"]);
+        assert_eq!(text.min.as_deref(), Some("10"));
+        assert_eq!(text.max.as_deref(), Some("b"));
+        assert_eq!(text.mean, None);
+        assert_eq!(stats(&[""]).min, None);
+    }
+
+    #[test]
+    fn test_estimate_distinct() {
+        let values: Vec<String> = (0..100_000).map(|i| i.to_string()).collect();
+        let mut accumulator = Accumulator::default();
+        for value in &values {
+            accumulator.update(value, "");
+        }
+        let estimate = accumulator.finish().distinct_count as f64;
+        assert!((estimate - 100_000.0).abs() < 5_000.0, "{}", estimate);
+    }
+}
@@ -0,0 +1,131 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// The number of bytes at the start of a stream that are kept to read them again.
+const REPLAY_LIMIT: usize = 1 << 20;
+
+/// A view on a stream that cannot seek, like standard input, which is read in a single
+/// forward pass. The start of the stream is kept in memory, so that the headers can be read
+/// before the records. Seeking backwards fails once more than the kept bytes have been read,
+/// seeking forwards skips the data.
+pub(crate) struct StreamSource {
+    reader: Box<dyn Read + Send>,
+    /// The bytes read from the start of the stream, until they exceed the replay limit.
+    replay: Option<Vec<u8>>,
+    replay_limit: usize,
+    /// The number of bytes read from the stream.
+    read: u64,
+    position: u64,
+}
+
+impl StreamSource {
+    pub(crate) fn new(reader: Box<dyn Read + Send>) -> StreamSource {
+        StreamSource::with_replay_limit(reader, REPLAY_LIMIT)
+    }
+
+    fn with_replay_limit(reader: Box<dyn Read + Send>, replay_limit: usize) -> StreamSource {
+        StreamSource {
+            reader,
+            replay: Some(Vec::new()),
+            replay_limit,
+            read: 0,
+            position: 0,
+        }
+    }
+}
+
+impl Read for StreamSource {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position < self.read {
+            let replay = self
+                .replay
+                .as_ref()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::Unsupported))?;
+            let start = self.position as usize;
+            let len = (replay.len() - start).min(buf.len());
+            buf[..len].copy_from_slice(&replay[start..start + len]);
+            self.position += len as u64;
+            return Ok(len);
+        }
+        let len = self.reader.read(buf)?;
+        if let Some(replay) = &mut self.replay {
+            if replay.len() + len > self.replay_limit {
+                self.replay = None;
+            } else {
+                replay.extend_from_slice(&buf[..len]);
+            }
+        }
+        self.read += len as u64;
+        self.position = self.read;
+        Ok(len)
+    }
+}
+
+impl Seek for StreamSource {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => offset,
+            SeekFrom::Current(offset) => self
+                .position
+                .checked_add_signed(offset)
+                .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?,
+            SeekFrom::End(_) => return Err(io::Error::from(io::ErrorKind::Unsupported)),
+        };
+        if target < self.position && self.replay.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the start of the input has already been discarded",
+            ));
+        }
+        if target <= self.read {
+            self.position = target;
+        } else {
+            self.position = self.read;
+            let remaining = target - self.position;
+            io::copy(&mut self.by_ref().take(remaining), &mut io::sink())?;
+        }
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileFormat, FileReader};
+
+    #[test]
+    fn test_replay_start_of_stream() {
+        let mut source = StreamSource::with_replay_limit(Box::new(&b"abcdefgh"[..]), 4);
+        let mut buf = [0; 3];
+        source.read_exact(&mut buf).unwrap();
+        assert_eq!(source.seek(SeekFrom::Start(1)).unwrap(), 1);
+        let mut text = String::new();
+        source.read_to_string(&mut text).unwrap();
+        assert_eq!(text, "bcdefgh");
+        assert!(source.seek(SeekFrom::Start(0)).is_err());
+        assert!(source.seek(SeekFrom::End(0)).is_err());
+    }
+
+    #[test]
+    fn test_seek_forward() {
+        let mut source = StreamSource::new(Box::new(&b"abcdefgh"[..]));
+        assert_eq!(source.seek(SeekFrom::Start(5)).unwrap(), 5);
+        let mut text = String::new();
+        source.read_to_string(&mut text).unwrap();
+        assert_eq!(text, "fgh");
+        source.seek(SeekFrom::Start(0)).unwrap();
+        text.clear();
+        source.read_to_string(&mut text).unwrap();
+        assert_eq!(text, "abcdefgh");
+    }
+
+    #[test]
+    fn test_read_csv_from_reader() {
+        let file = std::fs::File::open("tests/test.csv").unwrap();
+        let mut reader = FileReader::from_reader(file, FileFormat::Csv(','))
+            .expect("Failed to create FileReader");
+        assert_eq!(reader.headers().unwrap(), vec!["Name", "Age", "Country"]);
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2], vec!["Bob", "40", "Canada"]);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::FileError;
+use regex::Regex;
+use std::collections::HashMap;
+use std::io::{BufRead, ErrorKind, Read};
+
+/// The columns of the records read from syslog files and journal exports.
+pub(crate) const SYSLOG_COLUMNS: [&str; 4] = ["timestamp", "unit", "priority", "message"];
+
+/// A line in the traditional BSD syslog format (RFC 3164) as written by most syslog daemons,
+/// optionally with a high-precision timestamp instead of e.g. `Jan  5 10:00:00`.
+const BSD_SYSLOG: &str = r"^(?:<(?P<priority>\d{1,3})>)?(?P<timestamp>[A-Z][a-z]{2} [ \d]\d \d\d:\d\d:\d\d|\d{4}-\d\d-\d\dT\S+) \S+ (?:(?P<unit>[^:\[\s]+)(?:\[[^\]]*\])?: ?)?(?P<message>.*)$";
+/// A line in the syslog protocol format (RFC 5424).
+const IETF_SYSLOG: &str = r"^<(?P<priority>\d{1,3})>\d{1,2} (?P<timestamp>\S+) \S+ (?P<unit>\S+) \S+ \S+ (?:-|(?:\[(?:[^\]\\]|\\.)*\])+)(?: \x{FEFF}?(?P<message>.*))?$";
+
+/// Returns an iterator over the records of a syslog file together with their line numbers.
+/// Lines in both RFC 3164 and RFC 5424 format are read, the priority column contains the
+/// severity of messages with a priority value. Empty lines are skipped.
+pub(crate) fn read_syslog_records<R: BufRead>(
+    file: R,
+) -> impl Iterator<Item = (u64, Result<Vec<String>, FileError>)> {
+    let formats = [
+        Regex::new(IETF_SYSLOG).unwrap(),
+        Regex::new(BSD_SYSLOG).unwrap(),
+    ];
+    file.lines()
+        .enumerate()
+        .map(|(index, line)| (index as u64 + 1, line))
+        .filter(|(_, line)| !line.as_ref().is_ok_and(|line| line.trim().is_empty()))
+        .map(move |(number, line)| {
+            let record = line.map_err(FileError::from).and_then(|line| {
+                let captures = formats
+                    .iter()
+                    .find_map(|format| format.captures(&line))
+                    .ok_or(FileError::UnmatchedLogLine(number))?;
+                let field = |name| captures.name(name).map_or("", |field| field.as_str());
+                let priority = field("priority")
+                    .parse::<u8>()
+                    .map_or_else(|_| String::new(), |priority| (priority % 8).to_string());
+                let timestamp = match field("timestamp") {
+                    "-" => "",
+                    timestamp => timestamp,
+                };
+                let unit = match field("unit") {
+                    "-" => "",
+                    unit => unit,
+                };
+                Ok(vec![
+                    timestamp.to_string(),
+                    unit.to_string(),
+                    priority,
+                    field("message").to_string(),
+                ])
+            });
+            (number, record)
+        })
+}
+
+/// Returns an iterator over the entries of a journal export as written by
+/// `journalctl -o export` together with their numbers. The unit of entries without a
+/// systemd unit, like kernel messages, is their syslog identifier.
+pub(crate) fn read_journal_records<R: BufRead>(
+    mut file: R,
+) -> impl Iterator<Item = (u64, Result<Vec<String>, FileError>)> {
+    let mut number = 0;
+    let mut failed = false;
+    std::iter::from_fn(move || {
+        if failed {
+            return None;
+        }
+        let entry = read_journal_entry(&mut file).transpose()?;
+        number += 1;
+        failed = entry.is_err();
+        Some((number, entry.map(|fields| journal_record(&fields))))
+    })
+}
+
+/// Reads the fields of the next entry, skipping blank lines before it.
+fn read_journal_entry<R: BufRead>(
+    file: &mut R,
+) -> Result<Option<HashMap<String, Vec<u8>>>, FileError> {
+    let mut fields = HashMap::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if file.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        if line.is_empty() {
+            if fields.is_empty() {
+                continue;
+            }
+            break;
+        }
+        match line.iter().position(|&byte| byte == b'=') {
+            Some(equals) => {
+                let name = String::from_utf8_lossy(&line[..equals]).into_owned();
+                fields.insert(name, line[equals + 1..].to_vec());
+            }
+            None => {
+                // Binary fields are followed by their size as little-endian 64 bit integer,
+                // the data and a newline.
+                let mut size = [0; 8];
+                file.read_exact(&mut size)?;
+                let size = u64::from_le_bytes(size);
+                let mut value = Vec::new();
+                file.by_ref().take(size).read_to_end(&mut value)?;
+                let mut newline = [0; 1];
+                file.read_exact(&mut newline)?;
+                if value.len() as u64 != size || newline[0] != b'\n' {
+                    return Err(std::io::Error::new(
+                        ErrorKind::InvalidData,
+                        "invalid binary field in journal export",
+                    )
+                    .into());
+                }
+                fields.insert(String::from_utf8_lossy(&line).into_owned(), value);
+            }
+        }
+    }
+    Ok((!fields.is_empty()).then_some(fields))
+}
+
+fn journal_record(fields: &HashMap<String, Vec<u8>>) -> Vec<String> {
+    let field = |name: &str| {
+        fields.get(name).map_or_else(String::new, |value| {
+            String::from_utf8_lossy(value).into_owned()
+        })
+    };
+    let timestamp = field("__REALTIME_TIMESTAMP")
+        .parse()
+        .map_or_else(|_| String::new(), format_timestamp);
+    let unit = if fields.contains_key("_SYSTEMD_UNIT") {
+        field("_SYSTEMD_UNIT")
+    } else {
+        field("SYSLOG_IDENTIFIER")
+    };
+    vec![timestamp, unit, field("PRIORITY"), field("MESSAGE")]
+}
+
+/// Formats microseconds since the Unix epoch as an ISO 8601 timestamp in UTC.
+fn format_timestamp(microseconds: u64) -> String {
+    let seconds = microseconds / 1_000_000;
+    let days = (seconds / 86_400) as i64;
+    let time = seconds % 86_400;
+    // Converts days since the epoch to a date in the proleptic Gregorian calendar.
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60,
+        microseconds % 1_000_000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorPolicy, FileReader, RecordError};
+
+    #[test]
+    fn test_read_syslog() {
+        let mut reader =
+            FileReader::new("tests/test.syslog", None).expect("Failed to create FileReader");
+        assert_eq!(reader.headers().unwrap(), SYSLOG_COLUMNS);
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(
+            records,
+            vec![
+                vec![
+                    "Jan  5 10:00:00",
+                    "sshd",
+                    "",
+                    "Accepted publickey for alice"
+                ],
+                vec!["Oct 11 22:14:15", "su", "2", "'su root' failed for bob"],
+                vec![
+                    "2003-10-11T22:14:15.003Z",
+                    "evntslog",
+                    "5",
+                    "An application event"
+                ],
+                vec![
+                    "2024-01-05T10:00:01.123456+01:00",
+                    "kernel",
+                    "",
+                    "eth0: link up"
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unmatched_syslog_line() {
+        let mut reader = FileReader::new("tests/test.syslog", None)
+            .expect("Failed to create FileReader")
+            .error_policy(ErrorPolicy::Strict);
+        let error = reader
+            .try_records()
+            .unwrap()
+            .find_map(Result::err)
+            .map(RecordError::into_error);
+        assert_eq!(error, Some(FileError::UnmatchedLogLine(6)));
+    }
+
+    #[test]
+    fn test_read_journal_export() {
+        let mut reader =
+            FileReader::new("tests/test.export", None).expect("Failed to create FileReader");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(
+            records,
+            vec![
+                vec![
+                    "2024-01-05T10:00:00.123456Z",
+                    "ssh.service",
+                    "6",
+                    "Accepted publickey for alice"
+                ],
+                vec![
+                    "2024-01-05T10:00:01.000000Z",
+                    "kernel",
+                    "4",
+                    "line one\nline two"
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00.000000Z");
+        assert_eq!(
+            format_timestamp(951_782_400_000_001),
+            "2000-02-29T00:00:00.000001Z"
+        );
+    }
+}
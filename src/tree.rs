@@ -0,0 +1,555 @@
+use crate::multi::{file_records, until_error};
+use crate::{FileError, FileReader};
+use std::path::Path;
+
+/// A table found by a [`TreeReader`], i.e. a supported file in the traversed directory or
+/// archive.
+pub struct Table {
+    path: String,
+    name: String,
+    source: TableSource,
+}
+
+/// Where the records of a [`Table`] come from.
+enum TableSource {
+    /// A file in the traversed directory, which has not been opened yet.
+    File {
+        path: String,
+        delimiter: Option<char>,
+    },
+    /// A file inside an archive, which has not been opened yet.
+    #[cfg(feature = "archive")]
+    Member {
+        file_name: String,
+        delimiter: Option<char>,
+        location: std::sync::Arc<archive::Location>,
+    },
+    Reader(Box<FileReader>),
+}
+
+impl Table {
+    /// Returns the path of the file relative to the traversed directory or archive, with `/`
+    /// as separator. Files inside archives have the path of the archive as prefix, e.g.
+    /// `results.zip/calls.csv`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the name of the table, i.e. the file name without extensions.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the reader for the records of the table. Files are opened on the first call and
+    /// kept open until the table is dropped. Files inside archives are read from the archive
+    /// without extracting it.
+    pub fn reader(&mut self) -> Result<&mut FileReader, FileError> {
+        match &self.source {
+            TableSource::File { path, delimiter } => {
+                self.source = TableSource::Reader(Box::new(FileReader::new(path, *delimiter)?));
+            }
+            #[cfg(feature = "archive")]
+            TableSource::Member {
+                file_name,
+                delimiter,
+                location,
+            } => {
+                let reader = FileReader::from_opener(file_name, *delimiter, location.opener())?;
+                self.source = TableSource::Reader(Box::new(reader));
+            }
+            TableSource::Reader(_) => {}
+        }
+        match &mut self.source {
+            TableSource::Reader(reader) => Ok(reader),
+            _ => unreachable!("table has just been opened"),
+        }
+    }
+}
+
+/// A record read by [`TreeReader::records`] together with the table it belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeRecord {
+    /// The path of the table as returned by [`Table::path`].
+    pub path: String,
+    /// The name of the table as returned by [`Table::name`].
+    pub table: String,
+    /// The fields of the record.
+    pub record: Vec<String>,
+}
+
+/// A reader over every supported file in a directory tree. Unlike [`crate::MultiFileReader`],
+/// the files may have different formats and headers and are kept apart as [`Table`]s.
+/// Directories are traversed recursively in lexicographical order and files of unknown
+/// formats are skipped. With the `archive` feature, ZIP and (gzipped) tar archives are
+/// traversed as well, both on their own and inside the directory.
+/// CSV and TSV files are read with the given delimiter or otherwise `,` and `\t`.
+/// Files in the directory are only opened once they are read, see [`Table::reader`] and
+/// [`TreeReader::records`].
+///
+/// # Examples
+///
+/// ```
+/// use readervzrd::TreeReader;
+///
+/// let mut reader = TreeReader::new("tests/tree", None).expect("Failed to create TreeReader");
+/// for table in reader.tables() {
+///     let reader = table.reader().expect("Failed to open table");
+///     let headers = reader.headers().expect("Failed to get headers");
+///     println!("{} ({}): {:?}", table.path(), table.name(), headers);
+/// }
+/// ```
+pub struct TreeReader {
+    tables: Vec<Table>,
+}
+
+impl TreeReader {
+    /// Creates a new TreeReader instance for the given directory or archive.
+    pub fn new(path: &str, delimiter: Option<char>) -> Result<TreeReader, FileError> {
+        let path = Path::new(path);
+        let mut tables = Vec::new();
+        if path.is_dir() {
+            read_directory(path, "", delimiter, &mut tables)?;
+        } else {
+            #[cfg(feature = "archive")]
+            if let Some(kind) = archive::ArchiveKind::from_name(&path.to_string_lossy()) {
+                let location = archive::Location::File(path.to_path_buf());
+                kind.read(location.into(), "", delimiter, &mut tables)?;
+            }
+        }
+        if tables.is_empty() {
+            return Err(FileError::NoFiles);
+        }
+        Ok(TreeReader { tables })
+    }
+
+    /// Returns the tables found in the directory or archive.
+    pub fn tables(&mut self) -> &mut [Table] {
+        &mut self.tables
+    }
+
+    /// Returns an iterator over the records of all tables, one table after another, each
+    /// tagged with the path and name of its table. Files are opened once their first record is
+    /// requested, like by [`Table::reader`]. Iteration stops after an error opening or reading
+    /// a table, which is returned as the last item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::TreeReader;
+    ///
+    /// let mut reader = TreeReader::new("tests/tree", None).expect("Failed to create TreeReader");
+    /// for record in reader.records().unwrap() {
+    ///     let record = record.expect("Failed to read record");
+    ///     println!("{}: {:?}", record.path, record.record);
+    /// }
+    /// ```
+    pub fn records(
+        &mut self,
+    ) -> Result<impl Iterator<Item = Result<TreeRecord, FileError>> + '_, FileError> {
+        let records = self.tables.iter_mut().flat_map(|table| {
+            let (path, name) = (table.path.clone(), table.name.clone());
+            file_records(table.reader()).map(move |record| {
+                record.map(|record| TreeRecord {
+                    path: path.clone(),
+                    table: name.clone(),
+                    record,
+                })
+            })
+        });
+        Ok(until_error(records))
+    }
+}
+
+fn read_directory(
+    directory: &Path,
+    prefix: &str,
+    delimiter: Option<char>,
+    tables: &mut Vec<Table>,
+) -> Result<(), FileError> {
+    let mut entries = std::fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for entry in entries {
+        let Some(file_name) = entry.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let path = format!("{}{}", prefix, file_name);
+        if entry.is_dir() {
+            read_directory(&entry, &format!("{}/", path), delimiter, tables)?;
+            continue;
+        }
+        #[cfg(feature = "archive")]
+        if let Some(kind) = archive::ArchiveKind::from_name(file_name) {
+            let location = archive::Location::File(entry.clone());
+            kind.read(location.into(), &format!("{}/", path), delimiter, tables)?;
+            continue;
+        }
+        let Some(entry) = entry.to_str() else {
+            continue;
+        };
+        let delimiter = delimiter.or_else(|| default_delimiter(file_name));
+        if FileReader::supports(entry, delimiter)? {
+            tables.push(Table {
+                name: table_name(file_name),
+                path,
+                source: TableSource::File {
+                    path: entry.to_string(),
+                    delimiter,
+                },
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the delimiter of CSV and TSV files, possibly compressed.
+fn default_delimiter(file_name: &str) -> Option<char> {
+    let file_name = file_name.strip_suffix(".gz").unwrap_or(file_name);
+    match Path::new(file_name).extension()?.to_str()? {
+        "csv" => Some(','),
+        "tsv" => Some('\t'),
+        _ => None,
+    }
+}
+
+/// Returns the file name without compression suffix and extension, e.g. `calls` for
+/// `calls.csv.gz`.
+fn table_name(file_name: &str) -> String {
+    let file_name = file_name.strip_suffix(".gz").unwrap_or(file_name);
+    Path::new(file_name)
+        .file_stem()
+        .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned())
+}
+
+#[cfg(feature = "archive")]
+mod archive {
+    use super::{default_delimiter, table_name, Table, TableSource};
+    use crate::compression::Opener;
+    use crate::{FileError, FileFormat, FileReader};
+    use flate2::read::{DeflateDecoder, GzDecoder};
+    use std::fs::File;
+    use std::io::{self, Read, Seek};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use zip::result::ZipError;
+    use zip::CompressionMethod;
+
+    /// The archive formats that are traversed with the `archive` feature.
+    #[derive(Debug, Clone, Copy)]
+    pub(super) enum ArchiveKind {
+        Zip,
+        Tar,
+        TarGz,
+    }
+
+    /// Where the data of an archive or one of its members is found, so that a member can be
+    /// read from its start without extracting the archive.
+    pub(super) enum Location {
+        File(PathBuf),
+        Member {
+            /// The archive the member belongs to.
+            archive: Arc<Location>,
+            /// Whether the archive is gzip compressed as a whole, like `.tar.gz` files.
+            gzip: bool,
+            /// The position of the data of the member in the (decompressed) archive.
+            offset: u64,
+            /// The length of the stored data of the member.
+            size: u64,
+            /// Whether the data of the member is deflated, like in ZIP archives.
+            deflated: bool,
+        },
+    }
+
+    impl Location {
+        /// Opens the data at the location from its start.
+        fn open(&self) -> io::Result<Box<dyn Read + Send>> {
+            match self {
+                Location::File(path) => Ok(Box::new(File::open(path)?)),
+                Location::Member {
+                    archive,
+                    gzip,
+                    offset,
+                    size,
+                    deflated,
+                } => {
+                    let mut reader = archive.open()?;
+                    if *gzip {
+                        reader = Box::new(GzDecoder::new(reader));
+                    }
+                    let skipped = io::copy(&mut reader.by_ref().take(*offset), &mut io::sink())?;
+                    if skipped < *offset {
+                        return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+                    }
+                    let data = reader.take(*size);
+                    Ok(if *deflated {
+                        Box::new(DeflateDecoder::new(data))
+                    } else {
+                        Box::new(data)
+                    })
+                }
+            }
+        }
+
+        pub(super) fn opener(self: &Arc<Location>) -> Opener {
+            let location = Arc::clone(self);
+            Arc::new(move || location.open())
+        }
+    }
+
+    impl ArchiveKind {
+        pub(super) fn from_name(name: &str) -> Option<ArchiveKind> {
+            if name.ends_with(".zip") {
+                Some(ArchiveKind::Zip)
+            } else if name.ends_with(".tar") {
+                Some(ArchiveKind::Tar)
+            } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+                Some(ArchiveKind::TarGz)
+            } else {
+                None
+            }
+        }
+
+        /// Adds the tables of the supported members of the archive at the given location,
+        /// including those of nested archives. Only the headers of the archive are read here,
+        /// the members are read once their tables are opened.
+        pub(super) fn read(
+            self,
+            location: Arc<Location>,
+            prefix: &str,
+            delimiter: Option<char>,
+            tables: &mut Vec<Table>,
+        ) -> Result<(), FileError> {
+            let mut members = Vec::new();
+            match self {
+                ArchiveKind::Zip => match &*location {
+                    Location::File(path) => {
+                        read_zip_members(File::open(path)?, &location, &mut members)?
+                    }
+                    // Nested ZIP archives are only seekable in memory.
+                    Location::Member { .. } => {
+                        let mut data = Vec::new();
+                        location.open()?.read_to_end(&mut data)?;
+                        read_zip_members(io::Cursor::new(data), &location, &mut members)?
+                    }
+                },
+                ArchiveKind::Tar => read_tar_members(&location, false, &mut members)?,
+                ArchiveKind::TarGz => read_tar_members(&location, true, &mut members)?,
+            }
+            members.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (name, member) in members {
+                let path = format!("{}{}", prefix, name);
+                let file_name = name.rsplit('/').next().unwrap_or_default();
+                let member = Arc::new(member);
+                if let Some(kind) = ArchiveKind::from_name(file_name) {
+                    kind.read(member, &format!("{}/", path), delimiter, tables)?;
+                    continue;
+                }
+                let delimiter = delimiter.or_else(|| default_delimiter(file_name));
+                let format_name = file_name.strip_suffix(".gz").unwrap_or(file_name);
+                match FileFormat::from_file(format_name, delimiter) {
+                    #[cfg(feature = "sqlite")]
+                    Ok(FileFormat::Sqlite) => continue,
+                    Ok(FileFormat::Vcard) => {
+                        match FileReader::from_opener(file_name, delimiter, member.opener()) {
+                            Err(FileError::UnknownFileFormat) => continue,
+                            result => result?,
+                        };
+                    }
+                    Ok(_) => {}
+                    Err(FileError::UnknownFileFormat) => continue,
+                    Err(err) => return Err(err),
+                }
+                tables.push(Table {
+                    name: table_name(file_name),
+                    path,
+                    source: TableSource::Member {
+                        file_name: file_name.to_string(),
+                        delimiter,
+                        location: member,
+                    },
+                });
+            }
+            Ok(())
+        }
+    }
+
+    fn read_zip_members<R: Read + Seek>(
+        archive: R,
+        location: &Arc<Location>,
+        members: &mut Vec<(String, Location)>,
+    ) -> Result<(), FileError> {
+        let mut archive = zip::ZipArchive::new(archive)?;
+        for index in 0..archive.len() {
+            let member = archive.by_index_raw(index)?;
+            if !member.is_file() {
+                continue;
+            }
+            let deflated = match member.compression() {
+                CompressionMethod::Stored => false,
+                CompressionMethod::Deflated => true,
+                _ => {
+                    return Err(
+                        ZipError::UnsupportedArchive("unsupported compression method").into(),
+                    )
+                }
+            };
+            members.push((
+                member.name().to_string(),
+                Location::Member {
+                    archive: Arc::clone(location),
+                    gzip: false,
+                    offset: member.data_start(),
+                    size: member.compressed_size(),
+                    deflated,
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    fn read_tar_members(
+        location: &Arc<Location>,
+        gzip: bool,
+        members: &mut Vec<(String, Location)>,
+    ) -> Result<(), FileError> {
+        let mut reader = location.open()?;
+        if gzip {
+            reader = Box::new(GzDecoder::new(reader));
+        }
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let entry = entry?;
+            if entry.header().entry_type().is_file() {
+                let name = entry.path()?.to_string_lossy().into_owned();
+                members.push((
+                    name.trim_start_matches("./").to_string(),
+                    Location::Member {
+                        archive: Arc::clone(location),
+                        gzip,
+                        offset: entry.raw_file_position(),
+                        size: entry.size(),
+                        deflated: false,
+                    },
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_tables() {
+        let mut reader = TreeReader::new("tests/tree", None).expect("Failed to create TreeReader");
+        let tables: Vec<(&str, &str)> = reader
+            .tables()
+            .iter()
+            .map(|table| (table.path(), table.name()))
+            .collect();
+        let mut expected = vec![("a.csv", "a")];
+        if cfg!(feature = "archive") {
+            expected.extend([
+                ("results.zip/c.csv", "c"),
+                ("results.zip/nested/d.json.gz", "d"),
+                ("results.zip/nested/more.tar.gz/e.ndjson", "e"),
+            ]);
+        }
+        expected.push(("sub/b.tsv", "b"));
+        assert_eq!(tables, expected);
+    }
+
+    #[test]
+    fn test_tree_records() {
+        let mut reader = TreeReader::new("tests/tree", None).expect("Failed to create TreeReader");
+        let records: Vec<TreeRecord> = reader.records().unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(
+            records[0],
+            TreeRecord {
+                path: "a.csv".to_string(),
+                table: "a".to_string(),
+                record: vec!["1".to_string(), "x".to_string()],
+            }
+        );
+        let last = records.last().unwrap();
+        assert_eq!(last.path, "sub/b.tsv");
+        assert_eq!(last.record, vec!["3", "z"]);
+        if cfg!(feature = "archive") {
+            assert_eq!(records.len(), 5);
+            assert_eq!(records[3].record, vec!["5", "v"]);
+        }
+    }
+
+    #[test]
+    fn test_tree_table_reader() {
+        let mut reader = TreeReader::new("tests/tree", None).expect("Failed to create TreeReader");
+        let table = reader.tables().last_mut().unwrap();
+        assert_eq!(table.path(), "sub/b.tsv");
+        assert_eq!(
+            table.reader().unwrap().headers().unwrap(),
+            vec!["id", "label"]
+        );
+        let records: Vec<TreeRecord> = reader.records().unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(records.last().unwrap().record, vec!["3", "z"]);
+    }
+
+    #[test]
+    fn test_unreadable_table() {
+        let directory = std::env::temp_dir().join("readervzrd_tree_unreadable_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::copy("tests/tree/a.csv", directory.join("a.csv")).unwrap();
+        std::fs::copy("tests/tree/sub/b.tsv", directory.join("b.tsv")).unwrap();
+        let mut reader = TreeReader::new(directory.to_str().unwrap(), None)
+            .expect("Failed to create TreeReader");
+        std::fs::remove_file(directory.join("b.tsv")).unwrap();
+        let records: Vec<Result<TreeRecord, FileError>> = reader.records().unwrap().collect();
+        assert!(records[0].is_ok());
+        assert!(matches!(records.last(), Some(Err(FileError::IoError(_)))));
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        assert_eq!(
+            TreeReader::new("tests/parts/missing", None).err().unwrap(),
+            FileError::NoFiles
+        );
+    }
+
+    #[cfg(feature = "archive")]
+    #[test]
+    fn test_archive_members() {
+        let mut reader =
+            TreeReader::new("tests/members.zip", None).expect("Failed to create TreeReader");
+        let paths: Vec<&str> = reader.tables().iter().map(|table| table.path()).collect();
+        assert_eq!(paths, vec!["contacts.vcf", "inner.zip/x.csv", "stored.csv"]);
+        assert_eq!(
+            reader.tables()[0]
+                .reader()
+                .unwrap()
+                .records()
+                .unwrap()
+                .count(),
+            2
+        );
+        let records: Vec<TreeRecord> = reader.records().unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[3].record, vec!["2", "b"]);
+        assert_eq!(records[4].record, vec!["3", "c"]);
+    }
+
+    #[cfg(feature = "archive")]
+    #[test]
+    fn test_archive_tree() {
+        let mut reader =
+            TreeReader::new("tests/tree/results.zip", None).expect("Failed to create TreeReader");
+        assert_eq!(reader.tables().len(), 3);
+        assert_eq!(reader.tables()[0].path(), "c.csv");
+        assert_eq!(
+            reader.tables()[1].reader().unwrap().headers().unwrap(),
+            vec!["id", "label"]
+        );
+    }
+}
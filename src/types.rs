@@ -0,0 +1,303 @@
+use crate::{FileError, FileReader};
+use serde::{Deserialize, Serialize};
+
+/// The type of the values of a column as inferred by [`FileReader::infer_types`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColumnType {
+    /// Whole numbers that fit into an `i64`.
+    Integer,
+    /// Numbers with a fractional part or exponent, possibly mixed with integers.
+    Float,
+    /// `true` or `false` in any case.
+    Bool,
+    /// ISO 8601 calendar dates like `2024-01-31`.
+    Date,
+    /// ISO 8601 timestamps like `2024-01-31T12:00:00Z`, possibly mixed with dates.
+    DateTime,
+    /// Any other values.
+    String,
+}
+
+impl FileReader {
+    /// Infers the type of each column from the values of the first `sample_size` records.
+    /// Empty values, `NA` and the placeholder set by [`FileReader::null_output`] are ignored.
+    /// Each column gets the most specific type that all
+    /// remaining values conform to, columns without values are of type [`ColumnType::String`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::{ColumnType, FileReader};
+    ///
+    /// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+    /// let types = reader.infer_types(100).unwrap();
+    /// assert_eq!(types, vec![ColumnType::String, ColumnType::Integer, ColumnType::String]);
+    /// ```
+    pub fn infer_types(&mut self, sample_size: usize) -> Result<Vec<ColumnType>, FileError> {
+        let mut candidates = vec![Candidates::default(); self.headers()?.len()];
+        let null = self.normalization.null_output().to_string();
+        for record in self.records()?.take(sample_size) {
+            for (candidates, value) in candidates.iter_mut().zip(&record) {
+                candidates.update(value, &null);
+            }
+        }
+        Ok(candidates.iter().map(Candidates::column_type).collect())
+    }
+}
+
+/// The types a column may still have after inspecting some of its values.
+#[derive(Debug, Clone, Copy)]
+struct Candidates {
+    seen: bool,
+    integer: bool,
+    float: bool,
+    bool: bool,
+    date: bool,
+    datetime: bool,
+}
+
+impl Default for Candidates {
+    fn default() -> Self {
+        Candidates {
+            seen: false,
+            integer: true,
+            float: true,
+            bool: true,
+            date: true,
+            datetime: true,
+        }
+    }
+}
+
+impl Candidates {
+    fn update(&mut self, value: &str, null: &str) {
+        let value = value.trim();
+        if value.is_empty() || value == "NA" || value == null {
+            return;
+        }
+        self.seen = true;
+        self.integer &= value.parse::<i64>().is_ok();
+        self.float &= value.parse::<f64>().is_ok() && value.bytes().any(|b| b.is_ascii_digit());
+        self.bool &= value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false");
+        let date = is_date(value);
+        self.date &= date;
+        self.datetime &= date || is_datetime(value);
+    }
+
+    fn column_type(&self) -> ColumnType {
+        match self {
+            Candidates { seen: false, .. } => ColumnType::String,
+            Candidates { integer: true, .. } => ColumnType::Integer,
+            Candidates { float: true, .. } => ColumnType::Float,
+            Candidates { bool: true, .. } => ColumnType::Bool,
+            Candidates { date: true, .. } => ColumnType::Date,
+            Candidates { datetime: true, .. } => ColumnType::DateTime,
+            _ => ColumnType::String,
+        }
+    }
+}
+
+/// Returns whether the value is a valid date in the format `YYYY-MM-DD`.
+fn is_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return false;
+    }
+    let (Some(year), Some(month), Some(day)) = (
+        number(&value[..4]),
+        number(&value[5..7]),
+        number(&value[8..]),
+    ) else {
+        return false;
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+/// Returns whether the value is a timestamp in the format `YYYY-MM-DDTHH:MM[:SS[.f]]` with an
+/// optional `Z` or `±HH:MM` offset. A space is accepted in place of the `T`.
+fn is_datetime(value: &str) -> bool {
+    parse_datetime(value).is_some()
+}
+
+/// Returns the microseconds since the Unix epoch of a date or a timestamp as accepted by
+/// [`is_datetime`], see [`parse_datetime`].
+#[cfg(any(feature = "arrow", test))]
+pub(crate) fn timestamp_micros(value: &str) -> Option<i64> {
+    if is_date(value) {
+        return Some(days_since_epoch(value) * 86_400_000_000);
+    }
+    parse_datetime(value)
+}
+
+/// Parses a timestamp in the format of [`is_datetime`] to microseconds since the Unix epoch.
+/// Timestamps without offset are taken as UTC, a leap second is counted as the first second
+/// of the next minute and fractions beyond microseconds are truncated.
+fn parse_datetime(value: &str) -> Option<i64> {
+    if !value.is_ascii()
+        || value.len() < 16
+        || !is_date(&value[..10])
+        || !matches!(&value[10..11], "T" | " ")
+    {
+        return None;
+    }
+    let time = &value[11..];
+    let (time, offset) = match time.strip_suffix('Z') {
+        Some(time) => (time, 0),
+        None => match time.len().checked_sub(6).map(|split| time.split_at(split)) {
+            Some((time, offset))
+                if matches!(&offset[..1], "+" | "-") && is_time(&offset[1..], 23) =>
+            {
+                let minutes = minutes(&offset[1..]);
+                (
+                    time,
+                    if &offset[..1] == "-" {
+                        -minutes
+                    } else {
+                        minutes
+                    },
+                )
+            }
+            _ => (time, 0),
+        },
+    };
+    let (time, fraction) = time.split_once('.').unwrap_or((time, "0"));
+    let seconds = match time.len() {
+        5 => 0,
+        8 if &time[5..6] == ":" => number(&time[6..]).filter(|&seconds| seconds <= 60)?,
+        _ => return None,
+    };
+    if !is_time(&time[..5], 23)
+        || fraction.is_empty()
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let micros: i64 = format!("{:0<6}", &fraction[..fraction.len().min(6)])
+        .parse()
+        .ok()?;
+    let seconds = (minutes(&time[..5]) - offset) * 60 + i64::from(seconds);
+    Some(days_since_epoch(&value[..10]) * 86_400_000_000 + seconds * 1_000_000 + micros)
+}
+
+/// Returns the minutes of a time of day in the format `HH:MM` as accepted by [`is_time`].
+fn minutes(value: &str) -> i64 {
+    let hours = number(&value[..2]).unwrap_or_default();
+    let minutes = number(&value[3..]).unwrap_or_default();
+    i64::from(hours * 60 + minutes)
+}
+
+/// Returns the days since the Unix epoch of a date as accepted by [`is_date`].
+fn days_since_epoch(value: &str) -> i64 {
+    let part = |range: std::ops::Range<usize>| i64::from(number(&value[range]).unwrap_or_default());
+    let (year, month, day) = (part(0..4), part(5..7), part(8..10));
+    let year = if month <= 2 { year - 1 } else { year };
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    year.div_euclid(400) * 146_097 + day_of_era - 719_468
+}
+
+/// Returns whether the value is a time of day in the format `HH:MM` with at most `max_hour`
+/// hours.
+fn is_time(value: &str, max_hour: u32) -> bool {
+    value.is_ascii()
+        && value.len() == 5
+        && &value[2..3] == ":"
+        && number(&value[..2]).is_some_and(|hours| hours <= max_hour)
+        && number(&value[3..]).is_some_and(|minutes| minutes < 60)
+}
+
+/// Parses a non-empty string of ASCII digits.
+fn number(value: &str) -> Option<u32> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn infer(values: &[&str]) -> ColumnType {
+        let mut candidates = Candidates::default();
+        for value in values {
+            candidates.update(value, "");
+        }
+        candidates.column_type()
+    }
+
+    #[test]
+    fn test_infer_column_type() {
+        assert_eq!(infer(&["1", "-2", "", "NA"]), ColumnType::Integer);
+        let mut candidates = Candidates::default();
+        candidates.update("1", ".");
+        candidates.update(".", ".");
+        assert_eq!(candidates.column_type(), ColumnType::Integer);
+        assert_eq!(infer(&["1", "2.5", "1e3"]), ColumnType::Float);
+        assert_eq!(infer(&["true", "FALSE"]), ColumnType::Bool);
+        assert_eq!(infer(&["2024-02-29", "2023-12-31"]), ColumnType::Date);
+        assert_eq!(
+            infer(&[
+                "2024-02-29",
+                "2024-03-01T12:30:00Z",
+                "2024-03-01 08:00:00.5+01:00"
+            ]),
+            ColumnType::DateTime
+        );
+        assert_eq!(infer(&["2023-02-29"]), ColumnType::String);
+        assert_eq!(infer(&["1", "abc"]), ColumnType::String);
+        assert_eq!(infer(&["inf"]), ColumnType::String);
+        assert_eq!(infer(&[]), ColumnType::String);
+    }
+
+    #[test]
+    fn test_is_datetime() {
+        assert!(is_datetime("2024-01-31T23:59"));
+        assert!(is_datetime("2024-01-31T23:59:60.123-05:30"));
+        assert!(!is_datetime("2024-01-31T24:00"));
+        assert!(!is_datetime("2024-01-31T12:00:00."));
+        assert!(!is_datetime("2024-01-31X12:00"));
+        assert!(!is_datetime("Universitätsspital Zürich"));
+        assert!(!is_datetime("2024-01-31T12:00+01:0ä"));
+        assert!(!is_time("ä:00", 23));
+    }
+
+    #[test]
+    fn test_timestamp_micros() {
+        assert_eq!(timestamp_micros("1970-01-01"), Some(0));
+        assert_eq!(timestamp_micros("2024-02-29"), Some(1_709_164_800_000_000));
+        assert_eq!(timestamp_micros("1969-12-31T23:59"), Some(-60_000_000));
+        assert_eq!(
+            timestamp_micros("2024-03-01T00:00:00+01:00"),
+            Some(1_709_247_600_000_000)
+        );
+        assert_eq!(
+            timestamp_micros("2016-12-31 23:59:60.1234567Z"),
+            Some(1_483_228_800_123_456)
+        );
+        assert_eq!(
+            timestamp_micros("2024-01-31T12:00-05:30"),
+            timestamp_micros("2024-01-31T17:30")
+        );
+        assert_eq!(timestamp_micros("2024-01-31T24:00"), None);
+    }
+
+    #[test]
+    fn test_infer_types_json() {
+        let mut reader =
+            FileReader::new("tests/test.json", None).expect("Failed to create FileReader");
+        assert_eq!(
+            reader.infer_types(10).unwrap(),
+            vec![ColumnType::Integer, ColumnType::String, ColumnType::String]
+        );
+    }
+}
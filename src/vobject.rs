@@ -0,0 +1,164 @@
+/// The columns of the records read from iCalendar files, one per event.
+pub(crate) const ICS_COLUMNS: [&str; 9] = [
+    "UID",
+    "SUMMARY",
+    "DTSTART",
+    "DTEND",
+    "LOCATION",
+    "DESCRIPTION",
+    "STATUS",
+    "ORGANIZER",
+    "CATEGORIES",
+];
+
+/// The columns of the records read from vCard files, one per contact.
+pub(crate) const VCARD_COLUMNS: [&str; 10] = [
+    "FN", "N", "ORG", "TITLE", "EMAIL", "TEL", "ADR", "URL", "BDAY", "NOTE",
+];
+
+/// Returns whether data starts like a vCard file. `.vcf` is the extension of vCard files as
+/// well as of Variant Call Format files, which are told apart by this.
+pub(crate) fn is_vcard(start: &[u8]) -> bool {
+    start
+        .trim_ascii_start()
+        .get(..11)
+        .is_some_and(|begin| begin.eq_ignore_ascii_case(b"BEGIN:VCARD"))
+}
+
+/// Returns the records of the given component type, e.g. `VEVENT` or `VCARD`, with the values
+/// of the given properties. Properties occurring multiple times in a component, like several
+/// `EMAIL` addresses, are joined with `, `. Properties of nested components like alarms of an
+/// event are ignored.
+pub(crate) fn read_components(text: &str, component: &str, columns: &[&str]) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record: Option<Vec<Vec<String>>> = None;
+    let mut depth = 0;
+    for line in unfold(text) {
+        let Some((name, value)) = split_content_line(&line) else {
+            continue;
+        };
+        match (name.as_str(), record.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case(component) => {
+                record = Some(vec![Vec::new(); columns.len()]);
+                depth = 0;
+            }
+            ("BEGIN", Some(_)) => depth += 1,
+            ("END", Some(_)) if depth > 0 => depth -= 1,
+            ("END", Some(_)) => {
+                if let Some(values) = record.take() {
+                    records.push(values.into_iter().map(|values| values.join(", ")).collect());
+                }
+            }
+            (name, Some(values)) if depth == 0 => {
+                if let Some(index) = columns.iter().position(|column| *column == name) {
+                    values[index].push(unescape(value));
+                }
+            }
+            _ => {}
+        }
+    }
+    records
+}
+
+/// Joins folded content lines, i.e. lines continued on the next line starting with a space
+/// or tab.
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Splits a content line like `DTSTART;TZID=Europe/Berlin:20240101T090000` into its
+/// uppercase property name without group and parameters and its value.
+fn split_content_line(line: &str) -> Option<(String, &str)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(index, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(index),
+        _ => None,
+    })?;
+    let name = line[..colon].split(';').next().unwrap_or_default();
+    let name = name.rsplit('.').next().unwrap_or_default();
+    Some((name.to_ascii_uppercase(), &line[colon + 1..]))
+}
+
+/// Resolves the escape sequences of text values.
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n' | 'N') => unescaped.push('\n'),
+                Some(escaped) => unescaped.push(escaped),
+                None => unescaped.push('\\'),
+            },
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_events() {
+        let text = std::fs::read_to_string("tests/test.ics").unwrap();
+        let records = read_components(&text, "VEVENT", &ICS_COLUMNS);
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0],
+            vec![
+                "event-1@example.com",
+                "Team meeting, weekly",
+                "20240108T090000",
+                "20240108T100000",
+                "Room 1",
+                "Agenda:\nStatus updates",
+                "CONFIRMED",
+                "mailto:alice@example.com",
+                "WORK, MEETING"
+            ]
+        );
+        assert_eq!(records[1][1], "Holiday");
+        assert_eq!(records[1][3], "");
+    }
+
+    #[test]
+    fn test_is_vcard() {
+        assert!(is_vcard(b"\r\nbegin:vcard\r\nVERSION:4.0"));
+        assert!(!is_vcard(b"##fileformat=VCFv4.2\n#CHROM\tPOS"));
+        assert!(!is_vcard(b"BEGIN:VCAR"));
+    }
+
+    #[test]
+    fn test_read_contacts() {
+        let text = std::fs::read_to_string("tests/test.vcf").unwrap();
+        let records = read_components(&text, "VCARD", &VCARD_COLUMNS);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0][0], "John Doe");
+        assert_eq!(records[0][4], "john@example.com, john.doe@work.example.com");
+        assert_eq!(records[0][6], ";;Main Street 1;Springfield;;12345;USA");
+        assert_eq!(records[1][0], "Alice Smith");
+        assert_eq!(records[1][2], "Example Corp");
+    }
+
+    #[test]
+    fn test_split_content_line() {
+        assert_eq!(
+            split_content_line("item1.EMAIL;type=\"a:b\":x@example.com"),
+            Some(("EMAIL".to_string(), "x@example.com"))
+        );
+        assert_eq!(split_content_line("invalid"), None);
+    }
+}
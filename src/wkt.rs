@@ -0,0 +1,82 @@
+/// A two-dimensional position.
+pub(crate) type Position = (f64, f64);
+
+/// A geometry of a feature in a binary GIS format that can be rendered as WKT.
+/// Only the x and y coordinates are kept, z and m values are dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Geometry {
+    Point(Option<Position>),
+    LineString(Vec<Position>),
+    Polygon(Vec<Vec<Position>>),
+    MultiPoint(Vec<Position>),
+    MultiLineString(Vec<Vec<Position>>),
+    MultiPolygon(Vec<Vec<Vec<Position>>>),
+    #[cfg(feature = "gpkg")]
+    Collection(Vec<Geometry>),
+}
+
+impl Geometry {
+    /// Renders the geometry as well-known text.
+    pub(crate) fn wkt(&self) -> String {
+        let (kind, text) = match self {
+            Geometry::Point(position) => ("POINT", position.map(|p| format!("({})", point(p)))),
+            Geometry::LineString(line) => ("LINESTRING", non_empty(line, line_text)),
+            Geometry::Polygon(rings) => ("POLYGON", non_empty(rings, polygon_text)),
+            Geometry::MultiPoint(points) => ("MULTIPOINT", non_empty(points, line_text)),
+            Geometry::MultiLineString(lines) => ("MULTILINESTRING", non_empty(lines, polygon_text)),
+            Geometry::MultiPolygon(polygons) => (
+                "MULTIPOLYGON",
+                non_empty(polygons, |polygons| {
+                    list(polygons.iter().map(|rings| polygon_text(rings)))
+                }),
+            ),
+            #[cfg(feature = "gpkg")]
+            Geometry::Collection(geometries) => (
+                "GEOMETRYCOLLECTION",
+                non_empty(geometries, |geometries| {
+                    list(geometries.iter().map(Geometry::wkt))
+                }),
+            ),
+        };
+        format!("{} {}", kind, text.unwrap_or_else(|| "EMPTY".to_string()))
+    }
+}
+
+fn non_empty<T>(items: &[T], text: impl Fn(&[T]) -> String) -> Option<String> {
+    (!items.is_empty()).then(|| text(items))
+}
+
+fn point((x, y): Position) -> String {
+    format!("{} {}", x, y)
+}
+
+fn line_text(line: &[Position]) -> String {
+    list(line.iter().copied().map(point))
+}
+
+fn polygon_text(rings: &[Vec<Position>]) -> String {
+    list(rings.iter().map(|ring| line_text(ring)))
+}
+
+fn list(items: impl Iterator<Item = String>) -> String {
+    format!("({})", items.collect::<Vec<_>>().join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wkt() {
+        assert_eq!(Geometry::Point(Some((1.0, 2.5))).wkt(), "POINT (1 2.5)");
+        assert_eq!(Geometry::Point(None).wkt(), "POINT EMPTY");
+        assert_eq!(
+            Geometry::MultiPolygon(vec![
+                vec![vec![(0.0, 0.0), (0.0, 1.0), (1.0, 0.0), (0.0, 0.0)]],
+                vec![vec![(5.0, 5.0), (5.0, 6.0), (6.0, 5.0), (5.0, 5.0)]],
+            ])
+            .wkt(),
+            "MULTIPOLYGON (((0 0, 0 1, 1 0, 0 0)), ((5 5, 5 6, 6 5, 5 5)))"
+        );
+    }
+}
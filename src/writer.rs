@@ -0,0 +1,243 @@
+use crate::{FileError, FileFormat};
+use serde_json::{Map, Value};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+/// A struct that writes records to a file.
+/// The file can be in CSV, JSON or newline-delimited JSON format, which is determined by the
+/// file extension the same way as for [`crate::FileReader`].
+/// Dotted headers like `bank.account` are written as nested JSON objects, so that records
+/// read from nested JSON files can be written back to their original structure.
+///
+/// # Examples
+///
+/// ```
+/// use readervzrd::{FileReader, FileWriter};
+///
+/// let mut reader = FileReader::new("tests/test.csv", Some(',')).expect("Failed to create FileReader");
+/// let headers = reader.headers().expect("Failed to get headers");
+/// let path = std::env::temp_dir().join("readervzrd_doc_test.json");
+/// let mut writer = FileWriter::new(path.to_str().unwrap(), None).expect("Failed to create FileWriter");
+/// writer.write(&headers, reader.records().unwrap()).expect("Failed to write records");
+/// ```
+pub struct FileWriter {
+    file_format: FileFormat,
+    file: BufWriter<File>,
+}
+
+impl FileWriter {
+    /// Creates a new FileWriter instance. An existing file at the given path is truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use readervzrd::FileWriter;
+    ///
+    /// let path = std::env::temp_dir().join("readervzrd_doc_test.tsv");
+    /// let mut writer = FileWriter::new(path.to_str().unwrap(), Some('\t')).expect("Failed to create FileWriter");
+    /// ```
+    pub fn new(file_path: &str, delimiter: Option<char>) -> Result<FileWriter, FileError> {
+        let file_format = FileFormat::from_file(file_path, delimiter)?;
+        if !matches!(
+            file_format,
+            FileFormat::Csv(_) | FileFormat::Json | FileFormat::Ndjson
+        ) {
+            return Err(FileError::UnknownFileFormat);
+        }
+        let file = BufWriter::new(File::create(file_path)?);
+        Ok(FileWriter { file_format, file })
+    }
+
+    /// Writes the given headers and records to the file.
+    /// Every record has to contain exactly one field per header.
+    pub fn write<I>(&mut self, headers: &[String], records: I) -> Result<(), FileError>
+    where
+        I: IntoIterator<Item = Vec<String>>,
+    {
+        match self.file_format {
+            FileFormat::Csv(delimiter) => self.write_csv(delimiter, headers, records)?,
+            FileFormat::Json => self.write_json(headers, records)?,
+            FileFormat::Ndjson => self.write_ndjson(headers, records)?,
+            _ => unreachable!("Only CSV and JSON files can be written"),
+        }
+        self.file.flush()?;
+        Ok(())
+    }
+
+    fn write_csv<I>(
+        &mut self,
+        delimiter: char,
+        headers: &[String],
+        records: I,
+    ) -> Result<(), FileError>
+    where
+        I: IntoIterator<Item = Vec<String>>,
+    {
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(delimiter as u8)
+            .from_writer(&mut self.file);
+        writer.write_record(headers)?;
+        for (index, record) in records.into_iter().enumerate() {
+            if record.len() != headers.len() {
+                return Err(FileError::InvalidRecordLength(index));
+            }
+            writer.write_record(&record)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn write_json<I>(&mut self, headers: &[String], records: I) -> Result<(), FileError>
+    where
+        I: IntoIterator<Item = Vec<String>>,
+    {
+        write!(self.file, "[")?;
+        for (index, record) in records.into_iter().enumerate() {
+            if index > 0 {
+                write!(self.file, ",")?;
+            }
+            let object = unflatten_json_record(headers, record, index)?;
+            serde_json::to_writer(&mut self.file, &object)?;
+        }
+        writeln!(self.file, "]")?;
+        Ok(())
+    }
+
+    fn write_ndjson<I>(&mut self, headers: &[String], records: I) -> Result<(), FileError>
+    where
+        I: IntoIterator<Item = Vec<String>>,
+    {
+        for (index, record) in records.into_iter().enumerate() {
+            let object = unflatten_json_record(headers, record, index)?;
+            serde_json::to_writer(&mut self.file, &object)?;
+            writeln!(self.file)?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds a JSON object from the given record, nesting fields of dotted headers.
+fn unflatten_json_record(
+    headers: &[String],
+    record: Vec<String>,
+    index: usize,
+) -> Result<Value, FileError> {
+    if record.len() != headers.len() {
+        return Err(FileError::InvalidRecordLength(index));
+    }
+    let mut object = Map::new();
+    for (header, field) in headers.iter().zip(record) {
+        insert_json_field(&mut object, header, json_value(field));
+    }
+    Ok(Value::Object(object))
+}
+
+fn insert_json_field(object: &mut Map<String, Value>, header: &str, value: Value) {
+    let mut current = &mut *object;
+    let mut keys = header.split('.').peekable();
+    while let Some(key) = keys.next() {
+        if keys.peek().is_none() {
+            current.insert(key.to_string(), value);
+            return;
+        }
+        let entry = current
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        match entry {
+            Value::Object(inner) => current = inner,
+            _ => break,
+        }
+    }
+    // The path is blocked by a non-object value, so the header is kept as a flat key.
+    object.insert(header.to_string(), value);
+}
+
+/// Converts a field into a JSON value. Numbers and arrays that have been stringified by the
+/// reader are restored, all other fields are written as strings.
+fn json_value(field: String) -> Value {
+    match serde_json::from_str::<Value>(&field) {
+        Ok(value @ (Value::Number(_) | Value::Array(_))) if is_canonical(&value, &field) => value,
+        _ => Value::String(field),
+    }
+}
+
+fn is_canonical(value: &Value, field: &str) -> bool {
+    serde_json::to_string(value).is_ok_and(|serialized| serialized == field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileReader;
+
+    fn round_trip(source: &str, delimiter: Option<char>, target: &str) {
+        let mut reader = FileReader::new(source, delimiter).expect("Failed to create FileReader");
+        let headers = reader.headers().expect("Failed to get headers");
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+
+        let path = std::env::temp_dir().join(target);
+        let path = path.to_str().unwrap();
+        let mut writer = FileWriter::new(path, delimiter).expect("Failed to create FileWriter");
+        writer
+            .write(&headers, records.clone())
+            .expect("Failed to write records");
+
+        let mut reader = FileReader::new(path, delimiter).expect("Failed to create FileReader");
+        assert_eq!(reader.headers().expect("Failed to get headers"), headers);
+        assert_eq!(reader.records().unwrap().collect::<Vec<_>>(), records);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        round_trip("tests/test.csv", Some(','), "readervzrd_writer_test.csv");
+    }
+
+    #[test]
+    fn test_tsv_round_trip() {
+        round_trip("tests/test.tsv", Some('\t'), "readervzrd_writer_test.tsv");
+    }
+
+    #[test]
+    fn test_nested_json_round_trip() {
+        round_trip(
+            "tests/nested_test.json",
+            None,
+            "readervzrd_writer_test.json",
+        );
+    }
+
+    #[test]
+    fn test_ndjson_round_trip() {
+        round_trip(
+            "tests/inner_array_test.json",
+            None,
+            "readervzrd_writer_test.ndjson",
+        );
+    }
+
+    #[test]
+    fn test_json_output() {
+        let headers = vec!["name".to_string(), "bank.account".to_string()];
+        let record = vec!["John".to_string(), "123456".to_string()];
+        assert_eq!(
+            unflatten_json_record(&headers, record, 0).unwrap(),
+            serde_json::json!({"name": "John", "bank": {"account": 123456}})
+        );
+        assert_eq!(json_value("007".to_string()), Value::from("007"));
+        assert_eq!(
+            json_value("[\"dog\"]".to_string()),
+            serde_json::json!(["dog"])
+        );
+    }
+
+    #[test]
+    fn test_invalid_record_length() {
+        let path = std::env::temp_dir().join("readervzrd_writer_length_test.csv");
+        let mut writer = FileWriter::new(path.to_str().unwrap(), Some(','))
+            .expect("Failed to create FileWriter");
+        let result = writer.write(&["a".to_string()], vec![vec![], vec!["1".to_string()]]);
+        assert_eq!(result.err().unwrap(), FileError::InvalidRecordLength(0));
+        std::fs::remove_file(path).unwrap();
+    }
+}
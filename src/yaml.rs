@@ -0,0 +1,57 @@
+use crate::FileError;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Parses the items of a YAML file, i.e. the elements of a top-level sequence or the
+/// documents of a file with multiple documents separated by `---`. Empty documents are skipped.
+pub(crate) fn read_yaml_items(text: &str) -> Result<Vec<Value>, FileError> {
+    let mut documents = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(text) {
+        match Value::deserialize(document)? {
+            Value::Null => {}
+            document => documents.push(document),
+        }
+    }
+    if let [Value::Array(items)] = documents.as_mut_slice() {
+        return Ok(std::mem::take(items));
+    }
+    if !documents.iter().all(Value::is_object) {
+        return Err(FileError::InvalidJsonStructure);
+    }
+    Ok(documents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileReader;
+
+    #[test]
+    fn test_read_yaml_records() {
+        let mut reader =
+            FileReader::new("tests/test.yaml", None).expect("Failed to create FileReader");
+        assert_eq!(
+            reader.headers().unwrap(),
+            vec!["active", "address.city", "id", "name"]
+        );
+        let records: Vec<Vec<String>> = reader.records().unwrap().collect();
+        assert_eq!(
+            records,
+            vec![
+                vec!["true", "Berlin", "1", "John"],
+                vec!["false", "", "2", "Alice"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_yaml_documents() {
+        let items = read_yaml_items("a: 1\n---\na: 2\n").unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            read_yaml_items("just a scalar").err(),
+            Some(FileError::InvalidJsonStructure)
+        );
+        assert!(read_yaml_items("").unwrap().is_empty());
+    }
+}